@@ -1,13 +1,17 @@
+use crate::mnemonic;
 use crate::print_beauty;
 use crate::utils::{base64_decode, base64_encode, CustomErr};
 use crate::{payload::Payload, response::UnsafeTransactionResult, utils};
 use blake2b_simd::{Hash, Params};
 use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer};
 use hex;
+use k256::ecdsa::signature::Signer as _;
 use rand::rngs::OsRng;
+use rand::RngCore;
 use std::{env, error::Error, fmt::Display, str};
 
 const INTENT_VERSION_V0: u8 = 0;
+pub const SUI_PRIV_KEY_PREFIX: &str = "suiprivkey";
 const APPID_SUI: u8 = 0;
 pub enum IntentScope {
     TransactionData = 0,
@@ -16,6 +20,7 @@ pub enum IntentScope {
     PersonalMessage = 3,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SignatureScheme {
     ED25519 = 0x00,
     Secp256k1 = 0x01,
@@ -23,53 +28,166 @@ pub enum SignatureScheme {
     MultiSig = 0x03,
 }
 
+impl SignatureScheme {
+    pub fn from_flag(flag: u8) -> Result<Self, Box<dyn Error>> {
+        match flag {
+            0x00 => Ok(SignatureScheme::ED25519),
+            0x01 => Ok(SignatureScheme::Secp256k1),
+            0x02 => Ok(SignatureScheme::Secp256r1),
+            0x03 => Ok(SignatureScheme::MultiSig),
+            _ => Err(CustomErr::new_box(&format!(
+                "unknown signature scheme flag : {}",
+                flag
+            ))),
+        }
+    }
+
+    pub fn flag(&self) -> u8 {
+        *self as u8
+    }
+}
+
+enum SuiKeyPair {
+    Ed25519(Keypair),
+    Secp256k1(k256::ecdsa::SigningKey),
+    Secp256r1(p256::ecdsa::SigningKey),
+}
+
 pub struct SuiAccount {
-    pair: Keypair,
+    pair: SuiKeyPair,
 }
 
-pub fn msg_hash(msg: &Vec<u8>) -> Hash {
+pub fn msg_hash(msg: &[u8]) -> Hash {
     let mut state = Params::new().hash_length(32).to_state();
-    state.update(&msg).finalize()
+    state.update(msg).finalize()
+}
+
+pub fn intent_message(scope: IntentScope, msg_bytes: &[u8]) -> Vec<u8> {
+    let mut intent_message: Vec<u8> = vec![scope as u8, INTENT_VERSION_V0, APPID_SUI];
+    intent_message.extend_from_slice(msg_bytes);
+    intent_message
+}
+
+// personal message 先做 bcs 序列化 (vector<u8>)，与钱包的行为一致
+pub fn personal_message_bytes(message: &[u8]) -> Vec<u8> {
+    let mut bytes = utils::uleb128_encode(message.len());
+    bytes.extend_from_slice(message);
+    bytes
+}
+
+pub fn public_key_to_address(scheme: SignatureScheme, public_key: &[u8]) -> String {
+    let mut payload: Vec<u8> = vec![scheme.flag()];
+    payload.extend_from_slice(public_key);
+    let h = msg_hash(&payload);
+    format!("0x{}", h.to_hex())
 }
 
 impl SuiAccount {
     pub fn from_keystore(store_str: &str) -> Result<Self, Box<dyn Error>> {
         match base64_decode(store_str) {
             // 第一个字节是秘钥对类型
-            Ok(data) => SuiAccount::from_seed(&data[1..]),
+            Ok(data) => match data.split_first() {
+                Some((flag, secret)) => {
+                    SuiAccount::from_secret(SignatureScheme::from_flag(*flag)?, secret)
+                }
+                None => Err(CustomErr::new_box("keystore value empty!")),
+            },
             Err(err) => Err(Box::new(err)),
         }
     }
 
     pub fn from_seed(seed_bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
-        match SecretKey::from_bytes(&seed_bytes) {
+        match SecretKey::from_bytes(seed_bytes) {
             Err(err) => Err(Box::new(err)),
             Ok(secret) => {
                 let public: PublicKey = (&secret).into();
                 Ok(Self {
-                    pair: Keypair { secret, public },
+                    pair: SuiKeyPair::Ed25519(Keypair { secret, public }),
                 })
             }
         }
     }
 
-    // 通过env 获取 account, env 中value 保存 secret_key 的 bytes 的 hex 编码
+    // 根据秘钥类型，从 secret_key 的 bytes 创建 account
+    pub fn from_secret(scheme: SignatureScheme, secret: &[u8]) -> Result<Self, Box<dyn Error>> {
+        match scheme {
+            SignatureScheme::ED25519 => SuiAccount::from_seed(secret),
+            SignatureScheme::Secp256k1 => match k256::ecdsa::SigningKey::from_slice(secret) {
+                Err(err) => Err(Box::new(err)),
+                Ok(key) => Ok(Self {
+                    pair: SuiKeyPair::Secp256k1(key),
+                }),
+            },
+            SignatureScheme::Secp256r1 => match p256::ecdsa::SigningKey::from_slice(secret) {
+                Err(err) => Err(Box::new(err)),
+                Ok(key) => Ok(Self {
+                    pair: SuiKeyPair::Secp256r1(key),
+                }),
+            },
+            _ => Err(CustomErr::new_box(&format!(
+                "signature scheme {:?} is not supported",
+                scheme
+            ))),
+        }
+    }
+
+    // 使用 sui 钱包的默认路径，account_index 对应路径中的 account
+    pub fn from_mnemonic(
+        phrase: &str,
+        scheme: SignatureScheme,
+        account_index: u32,
+    ) -> Result<Self, Box<dyn Error>> {
+        let path = mnemonic::default_derivation_path(scheme, account_index)?;
+        SuiAccount::from_mnemonic_with_path(phrase, scheme, &path)
+    }
+
+    pub fn from_mnemonic_with_path(
+        phrase: &str,
+        scheme: SignatureScheme,
+        path: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let secret = mnemonic::derive_secret(phrase, scheme, path)?;
+        SuiAccount::from_secret(scheme, &secret)
+    }
+
+    // 生成新的助记词以及第一个账号
+    pub fn new_with_mnemonic(
+        scheme: SignatureScheme,
+        word_count: usize,
+    ) -> Result<(Self, String), Box<dyn Error>> {
+        let phrase = mnemonic::generate_mnemonic(word_count)?;
+        let account = SuiAccount::from_mnemonic(&phrase, scheme, 0)?;
+        Ok((account, phrase))
+    }
+
+    // bech32 编码的私钥，数据部分为 flag || secret_key
+    pub fn from_bech32(value: &str) -> Result<Self, Box<dyn Error>> {
+        let (hrp, data) = bech32::decode(value.trim())?;
+        if hrp.as_str() != SUI_PRIV_KEY_PREFIX {
+            return Err(CustomErr::new_box(&format!(
+                "private key prefix should be {}",
+                SUI_PRIV_KEY_PREFIX
+            )));
+        }
+        match data.split_first() {
+            Some((flag, secret)) => {
+                SuiAccount::from_secret(SignatureScheme::from_flag(*flag)?, secret)
+            }
+            None => Err(CustomErr::new_box("private key empty!")),
+        }
+    }
+
+    // 通过env 获取 account, env 中value 保存 suiprivkey 开头的 bech32 私钥，或者 ed25519 secret_key 的 bytes 的 hex 编码
     pub fn from_env(name: &str) -> Result<Self, Box<dyn Error>> {
         match env::var(name) {
             Ok(val) => {
-                if val == "" {
+                if val.is_empty() {
                     Err(Box::new(CustomErr::new("env value empty!")))
+                } else if val.starts_with(SUI_PRIV_KEY_PREFIX) {
+                    SuiAccount::from_bech32(&val)
                 } else {
-                    let key_bytes = hex::decode(val).unwrap();
-                    match SecretKey::from_bytes(&key_bytes) {
-                        Err(err) => Err(Box::new(err)),
-                        Ok(secret) => {
-                            let public: PublicKey = (&secret).into();
-                            Ok(SuiAccount {
-                                pair: Keypair { secret, public },
-                            })
-                        }
-                    }
+                    let key_bytes = hex::decode(val)?;
+                    SuiAccount::from_seed(&key_bytes)
                 }
             }
             Err(err) => Err(Box::new(err)),
@@ -79,35 +197,155 @@ impl SuiAccount {
     pub fn new_account() -> Self {
         let mut csprng = OsRng {};
         let pair = Keypair::generate(&mut csprng);
-        Self { pair }
+        Self {
+            pair: SuiKeyPair::Ed25519(pair),
+        }
+    }
+
+    pub fn new_secp256k1_account() -> Self {
+        let mut csprng = OsRng {};
+        let mut secret = [0u8; 32];
+        // 随机值超出曲线阶时重新生成
+        loop {
+            csprng.fill_bytes(&mut secret);
+            if let Ok(key) = k256::ecdsa::SigningKey::from_slice(&secret) {
+                return Self {
+                    pair: SuiKeyPair::Secp256k1(key),
+                };
+            }
+        }
+    }
+
+    pub fn new_secp256r1_account() -> Self {
+        let mut csprng = OsRng {};
+        let mut secret = [0u8; 32];
+        loop {
+            csprng.fill_bytes(&mut secret);
+            if let Ok(key) = p256::ecdsa::SigningKey::from_slice(&secret) {
+                return Self {
+                    pair: SuiKeyPair::Secp256r1(key),
+                };
+            }
+        }
+    }
+
+    pub fn generate(scheme: SignatureScheme) -> Result<Self, Box<dyn Error>> {
+        match scheme {
+            SignatureScheme::ED25519 => Ok(SuiAccount::new_account()),
+            SignatureScheme::Secp256k1 => Ok(SuiAccount::new_secp256k1_account()),
+            SignatureScheme::Secp256r1 => Ok(SuiAccount::new_secp256r1_account()),
+            _ => Err(CustomErr::new_box(&format!(
+                "signature scheme {:?} is not supported",
+                scheme
+            ))),
+        }
+    }
+
+    pub fn scheme(&self) -> SignatureScheme {
+        match &self.pair {
+            SuiKeyPair::Ed25519(_) => SignatureScheme::ED25519,
+            SuiKeyPair::Secp256k1(_) => SignatureScheme::Secp256k1,
+            SuiKeyPair::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+
+    // ed25519 为 32 bytes，secp256k1 / secp256r1 为 33 bytes 的压缩公钥
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        match &self.pair {
+            SuiKeyPair::Ed25519(pair) => pair.public.as_bytes().to_vec(),
+            SuiKeyPair::Secp256k1(key) => key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            SuiKeyPair::Secp256r1(key) => key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        }
     }
+
+    // sui.aliases 中使用的格式: base64(flag || public_key)
+    pub fn public_key_base64(&self) -> String {
+        let mut data: Vec<u8> = vec![self.scheme().flag()];
+        data.append(&mut self.public_key_bytes());
+        base64_encode(&data)
+    }
+
     pub fn to_address(&self) -> String {
-        let mut payload: Vec<u8> = vec![0];
-        payload.extend_from_slice(self.pair.public.as_bytes());
-        let h = msg_hash(&payload);
-        format!("0x{}", h.to_hex())
+        public_key_to_address(self.scheme(), &self.public_key_bytes())
+    }
+
+    fn secret_bytes(&self) -> Vec<u8> {
+        match &self.pair {
+            SuiKeyPair::Ed25519(pair) => pair.secret.as_bytes().to_vec(),
+            SuiKeyPair::Secp256k1(key) => key.to_bytes().to_vec(),
+            SuiKeyPair::Secp256r1(key) => key.to_bytes().to_vec(),
+        }
     }
 
     pub fn dump_hex_seed(&self) -> String {
-        let secret = &self.pair.secret;
-        hex::encode(secret.as_bytes())
+        hex::encode(self.secret_bytes())
+    }
+
+    // 导出为 sui 钱包和 cli 使用的 suiprivkey 格式
+    pub fn to_bech32(&self) -> String {
+        let mut data: Vec<u8> = vec![self.scheme().flag()];
+        data.append(&mut self.secret_bytes());
+        let hrp = bech32::Hrp::parse_unchecked(SUI_PRIV_KEY_PREFIX);
+        bech32::encode::<bech32::Bech32>(hrp, &data).unwrap()
+    }
+
+    // 导出为 sui.keystore 中的格式: base64(flag || secret_key)
+    pub fn to_keystore(&self) -> String {
+        let mut data: Vec<u8> = vec![self.scheme().flag()];
+        data.append(&mut self.secret_bytes());
+        base64_encode(&data)
+    }
+
+    // ed25519 直接对 digest 签名，secp256k1 / secp256r1 对 digest 做 sha256 后签名
+    fn sign_digest(&self, digest: &[u8]) -> Vec<u8> {
+        match &self.pair {
+            SuiKeyPair::Ed25519(pair) => {
+                let signature: Signature = pair.sign(digest);
+                signature.to_bytes().to_vec()
+            }
+            SuiKeyPair::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature = key.sign(digest);
+                let signature = signature.normalize_s().unwrap_or(signature);
+                signature.to_bytes().to_vec()
+            }
+            SuiKeyPair::Secp256r1(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(digest);
+                let signature = signature.normalize_s().unwrap_or(signature);
+                signature.to_bytes().to_vec()
+            }
+        }
     }
 
     pub fn sign_data(&self, msg_b64: &str, scope: IntentScope) -> Vec<u8> {
-        let scheme: SignatureScheme = SignatureScheme::ED25519;
         let msg_bytes = base64_decode(msg_b64).unwrap();
-        let pub_bytes: &[u8; 32] = self.pair.public.as_bytes();
-        let mut intent_message: Vec<u8> = vec![scope as u8, INTENT_VERSION_V0, APPID_SUI];
-        intent_message.append(&mut msg_bytes.to_vec());
-        print_beauty!("intent : {}", base64_encode(&intent_message));
-        let h = msg_hash(&intent_message);
+        self.sign_intent_message(&intent_message(scope, &msg_bytes))
+    }
+
+    pub fn sign_personal_message(&self, message: &[u8]) -> Vec<u8> {
+        self.sign_intent_message(&intent_message(
+            IntentScope::PersonalMessage,
+            &personal_message_bytes(message),
+        ))
+    }
+
+    fn sign_intent_message(&self, intent_message: &[u8]) -> Vec<u8> {
+        print_beauty!("intent : {}", base64_encode(intent_message));
+        let h = msg_hash(intent_message);
         print_beauty!("blake2b: {}", hex::encode(h.as_bytes()));
-        let signature: Signature = self.pair.sign(h.as_bytes());
-        let mut wrapper_signature: Vec<u8> = vec![scheme as u8];
-        wrapper_signature.append(&mut signature.to_bytes().to_vec());
-        wrapper_signature.append(&mut pub_bytes.to_vec());
+        let mut wrapper_signature: Vec<u8> = vec![self.scheme().flag()];
+        wrapper_signature.append(&mut self.sign_digest(h.as_bytes()));
+        wrapper_signature.append(&mut self.public_key_bytes());
         wrapper_signature
     }
+
     pub fn sign_unsafe_transaciton(&self, unsafe_transaction: &UnsafeTransactionResult) -> Payload {
         let result = self.sign_data(&unsafe_transaction.tx_bytes, IntentScope::TransactionData);
         Payload::safe_transaction_block_payload(
@@ -164,4 +402,100 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_secp256k1_from_keystore() {
+        let secret: [u8; 32] = [
+            59, 148, 11, 85, 134, 130, 61, 253, 2, 174, 59, 70, 27, 180, 51, 107, 94, 203, 174,
+            253, 102, 39, 170, 146, 46, 252, 4, 143, 236, 12, 136, 28,
+        ];
+        let mut store_bytes = vec![SignatureScheme::Secp256k1.flag()];
+        store_bytes.extend_from_slice(&secret);
+        let account = SuiAccount::from_keystore(&base64_encode(&store_bytes)).unwrap();
+        assert_eq!(account.scheme(), SignatureScheme::Secp256k1);
+        assert_eq!(account.dump_hex_seed(), hex::encode(secret));
+        assert_eq!(
+            account.public_key_bytes(),
+            vec![
+                2, 29, 21, 35, 7, 198, 183, 43, 14, 208, 65, 139, 14, 112, 205, 128, 231, 245, 41,
+                91, 141, 134, 245, 114, 45, 63, 82, 19, 251, 210, 57, 79, 54
+            ]
+        );
+
+        let mut address_payload = vec![0x01];
+        address_payload.extend_from_slice(&account.public_key_bytes());
+        assert_eq!(
+            account.to_address(),
+            format!("0x{}", msg_hash(&address_payload).to_hex())
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_sign_data() {
+        use k256::ecdsa::signature::Verifier;
+
+        let account = SuiAccount::generate(SignatureScheme::Secp256k1).unwrap();
+        let data = [1u8, 2, 3, 4];
+        let signature = account.sign_data(&base64_encode(&data), IntentScope::TransactionData);
+        assert_eq!(signature.len(), 1 + 64 + 33);
+        assert_eq!(signature[0], SignatureScheme::Secp256k1.flag());
+        assert_eq!(signature[65..].to_vec(), account.public_key_bytes());
+
+        let digest = msg_hash(&[0, 0, 0, 1, 2, 3, 4]);
+        let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&signature[65..]).unwrap();
+        let sig = k256::ecdsa::Signature::from_slice(&signature[1..65]).unwrap();
+        assert!(sig.normalize_s().is_none());
+        assert!(key.verify(digest.as_bytes(), &sig).is_ok());
+    }
+
+    #[test]
+    fn test_secp256r1_keystore_and_sign() {
+        use p256::ecdsa::signature::Verifier;
+
+        let account = SuiAccount::generate(SignatureScheme::Secp256r1).unwrap();
+        let store_str = account.to_keystore();
+        assert_eq!(
+            base64_decode(&store_str).unwrap()[0],
+            SignatureScheme::Secp256r1.flag()
+        );
+
+        let loaded = SuiAccount::from_keystore(&store_str).unwrap();
+        assert_eq!(loaded.scheme(), SignatureScheme::Secp256r1);
+        assert_eq!(loaded.to_address(), account.to_address());
+        assert_eq!(loaded.public_key_bytes().len(), 33);
+
+        let signature =
+            loaded.sign_data(&base64_encode(&[9u8, 8, 7]), IntentScope::TransactionData);
+        assert_eq!(signature[0], SignatureScheme::Secp256r1.flag());
+        let digest = msg_hash(&[0, 0, 0, 9, 8, 7]);
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&signature[65..]).unwrap();
+        let sig = p256::ecdsa::Signature::from_slice(&signature[1..65]).unwrap();
+        assert!(sig.normalize_s().is_none());
+        assert!(key.verify(digest.as_bytes(), &sig).is_ok());
+    }
+
+    #[test]
+    fn test_bech32_private_key() {
+        let account =
+            SuiAccount::from_keystore("AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF").unwrap();
+        let encoded = account.to_bech32();
+        assert!(encoded.starts_with("suiprivkey1"));
+        let decoded = SuiAccount::from_bech32(&encoded).unwrap();
+        assert_eq!(
+            decoded.to_address(),
+            "0x0a27f6f7d3b7907fbcc4265ee8e63f5447312a8f53fb270a36f892e6f264008f"
+        );
+
+        for scheme in [SignatureScheme::Secp256k1, SignatureScheme::Secp256r1] {
+            let account = SuiAccount::generate(scheme).unwrap();
+            let decoded = SuiAccount::from_bech32(&account.to_bech32()).unwrap();
+            assert_eq!(decoded.scheme(), scheme);
+            assert_eq!(decoded.to_address(), account.to_address());
+        }
+
+        let other_prefix =
+            bech32::encode::<bech32::Bech32>(bech32::Hrp::parse("suipubkey").unwrap(), &[0u8; 33])
+                .unwrap();
+        assert!(SuiAccount::from_bech32(&other_prefix).is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
-use sui_rust_operator::account::SuiAccount;
+use sui_rust_operator::{account::SuiAccount, keystore::Keystore};
 
-use std::thread;
+use std::{env, thread};
 
 #[tokio::main]
 async fn main() {
@@ -35,8 +35,22 @@ async fn main() {
         let guard = accounts_main.lock().unwrap();
         println!("Total accounts generated: {}", guard.len());
 
-        for account in &*guard {
-            println!("Account: {} , seed : {} ", account, account.dump_hex_seed());
+        // 指定 keystore 路径时保存到 keystore，否则打印 seed
+        match env::args().nth(1) {
+            Some(path) => {
+                let mut store = Keystore::load_or_create(&path);
+                for account in &*guard {
+                    store.add_account(account).unwrap();
+                    println!("Account: {} saved", account);
+                }
+                store.save().unwrap();
+                println!("keystore saved : {}", store.path());
+            }
+            None => {
+                for account in &*guard {
+                    println!("Account: {} , seed : {} ", account, account.dump_hex_seed());
+                }
+            }
         }
     }
 }
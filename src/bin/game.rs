@@ -1,31 +1,24 @@
 use serde_json::Value;
 use sui_rust_operator::{
-    client,
-    keystore::Keystore,
-    network,
-    payload::QueryOption,
-    print_beauty,
-    response::CoinInfo,
-    utils::{self, ADVISE_GAS_BUDGET},
+    client, gas, keystore::Keystore, network, payload::QueryOption, print_beauty,
+    response::CoinInfo, utils,
 };
 
 #[tokio::main]
 async fn main() {
     let network = network::default();
     let store = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    let account = store.account_from_env().unwrap();
 
     print_beauty!("network is : {}", network);
     print_beauty!("account is : {}", account.to_address());
     print_beauty!("network gateway is : {}", network.get_gateway());
 
     let client = client::default_client(network);
-    for balance in client
-        .get_all_balances(account.to_address())
-        .await
-        .unwrap()
-        .result
-    {
+    let gas_budget = gas::from_env();
+    print_beauty!("gas budget is : {}", gas_budget);
+    let fixed_budget = matches!(gas_budget, gas::GasBudget::Fixed(_));
+    for balance in client.get_all_balances(account.to_address()).await.unwrap() {
         print_beauty!(
             "balance {} , count :  {} , total: {}",
             balance.coin_type,
@@ -37,13 +30,14 @@ async fn main() {
     print_beauty!("loading gas list ...");
 
     let gas_result = client
-        .get_avaliable_gas(account.to_address(), ADVISE_GAS_BUDGET)
+        .get_avaliable_gas(account.to_address(), gas_budget.probe())
         .await;
-    let gas_object: CoinInfo = if gas_result.is_err() {
-        print_beauty!("sorry,you don't have enough gas. Bye!!!");
-        CoinInfo::default()
-    } else {
-        gas_result.unwrap()
+    let gas_object: CoinInfo = match gas_result {
+        Ok(gas) => gas,
+        Err(_) => {
+            print_beauty!("sorry,you don't have enough gas. Bye!!!");
+            CoinInfo::default()
+        }
     };
 
     print_beauty!("publish example module : ");
@@ -53,21 +47,25 @@ async fn main() {
     print_beauty!("dependencies : {:?}", compiled.dependencies);
 
     print_beauty!("now publish ...");
-    let pub_info = client
-        .unsafe_publish(
-            account.to_address(),
-            compiled.modules,
-            compiled.dependencies,
-            gas_object.coin_object_id.to_string(),
-            ADVISE_GAS_BUDGET,
-        )
+    let mut pub_info = client
+        .build_with_budget(gas_budget, |budget| {
+            client.unsafe_publish(
+                account.to_address(),
+                compiled.modules.clone(),
+                compiled.dependencies.clone(),
+                gas_object.coin_object_id.to_string(),
+                budget,
+            )
+        })
         .await
         .unwrap();
-    print_beauty!("transcation bytes : {}", pub_info.result.tx_bytes);
+    print_beauty!("transcation bytes : {}", pub_info.tx_bytes);
+    if fixed_budget {
+        pub_info.set_dry_run();
+    }
     print_beauty!("sign with account.");
 
     let effect = pub_info
-        .result
         .with_signed_execute(&client, &account)
         .await
         .unwrap();
@@ -76,53 +74,53 @@ async fn main() {
         "publish reuslt : {}",
         serde_json::to_string_pretty(&effect).unwrap()
     );
-    print_beauty!(
-        "transaction link : {}",
-        client
-            .network
-            .transaction_link(&effect.result.digest.to_string())
-    );
+    if let Some(link) = client.network.transaction_link(&effect.digest) {
+        print_beauty!("transaction link : {}", link);
+    }
 
-    if let Some(effects) = &effect.result.effects {
+    if let Some(effects) = &effect.effects {
         let imutables = effects.find_imutable_object();
-        if imutables.len() > 0 {
+        if !imutables.is_empty() {
             let package_id = imutables[0].to_string();
             print_beauty!("just publish one module : {}", package_id);
-            match client
-                .unsafe_move_call(
-                    account.to_address(),
-                    package_id.clone(),
-                    String::from("hello_world"),
-                    "mint".to_string(),
-                    vec![],
-                    vec![],
-                    gas_object.coin_object_id.to_string(),
-                    ADVISE_GAS_BUDGET,
-                )
+            if let Ok(mut result) = client
+                .build_with_budget(gas_budget, |budget| {
+                    client.unsafe_move_call(
+                        account.to_address(),
+                        package_id.clone(),
+                        String::from("hello_world"),
+                        "mint".to_string(),
+                        vec![],
+                        vec![],
+                        gas_object.coin_object_id.to_string(),
+                        budget,
+                    )
+                })
                 .await
             {
-                Ok(result) => {
-                    let signed_payload = account.sign_unsafe_transaciton(&result.result);
-                    let result = client.send_payload_effect(&signed_payload).await.unwrap();
-                    print_beauty!("mint transaction done : {}", result.result.digest);
+                if fixed_budget {
+                    result.set_dry_run();
+                }
+                let result = result.with_signed_execute(&client, &account).await.unwrap();
+                print_beauty!("mint transaction done : {}", result.digest);
 
-                    let struct_type =
-                        format!("{}::hello_world::HelloWorldObject", package_id.clone());
-                    let query = QueryOption::with_strutc_type(struct_type);
+                let struct_type = format!("{}::hello_world::HelloWorldObject", package_id.clone());
+                let query = QueryOption::with_strutc_type(struct_type);
 
-                    print_beauty!("query options : {}", serde_json::to_string(&query).unwrap());
+                print_beauty!("query options : {}", serde_json::to_string(&query).unwrap());
 
-                    let objects = client
-                        .get_owned_objects(account.to_address(), query, None, None)
-                        .await
-                        .unwrap();
-                    for object in objects.result.data {
-                        print_beauty!("HelloWorldObject with id  : {}", object.data.object_id);
+                let objects = client
+                    .get_owned_objects(account.to_address(), query, None, None)
+                    .await
+                    .unwrap();
+                for object in objects.data {
+                    print_beauty!("HelloWorldObject with id  : {}", object.data.object_id);
 
-                        print_beauty!("now remove this object .");
+                    print_beauty!("now remove this object .");
 
-                        match client
-                            .unsafe_move_call(
+                    if let Ok(mut data) = client
+                        .build_with_budget(gas_budget, |budget| {
+                            client.unsafe_move_call(
                                 account.to_address(),
                                 package_id.clone(),
                                 String::from("hello_world"),
@@ -130,29 +128,21 @@ async fn main() {
                                 vec![],
                                 vec![Value::String(object.data.object_id.to_string())],
                                 gas_object.coin_object_id.to_string(),
-                                ADVISE_GAS_BUDGET,
+                                budget,
                             )
-                            .await
-                        {
-                            Ok(data) => {
-                                let effect = data
-                                    .result
-                                    .with_signed_execute(&client, &account)
-                                    .await
-                                    .unwrap();
-
-                                print_beauty!(
-                                    "destroy transaction link : {}",
-                                    client
-                                        .network
-                                        .transaction_link(&effect.result.digest.to_string())
-                                );
-                            }
-                            _ => {}
+                        })
+                        .await
+                    {
+                        if fixed_budget {
+                            data.set_dry_run();
+                        }
+                        let effect = data.with_signed_execute(&client, &account).await.unwrap();
+
+                        if let Some(link) = client.network.transaction_link(&effect.digest) {
+                            print_beauty!("destroy transaction link : {}", link);
                         }
                     }
                 }
-                _ => {}
             }
         }
     }
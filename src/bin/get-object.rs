@@ -8,10 +8,9 @@ async fn main() {
         "0x2dfc31f14cc8b0040407e818568fa37e95e52281b684b67050ab32a16942d955".to_string();
     println!("gateway is : {}", myclient.network.get_gateway());
     println!("network is : {}", myclient.network);
-    println!(
-        "object link is : {} ",
-        myclient.network.object_link(&object_id)
-    );
+    if let Some(link) = myclient.network.object_link(&object_id) {
+        println!("object link is : {} ", link);
+    }
 
     match myclient.get_object_id(&object_id).await {
         Ok(object) => {
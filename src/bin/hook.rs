@@ -1,9 +1,11 @@
-use std::vec;
+use std::{env, vec};
 use sui_rust_operator::{
     client,
+    endpoint::SelectionPolicy,
+    gas,
     hook::{HookCaller, Target},
     hookserver,
-    keystore::Keystore,
+    keystore::{self, Keystore},
     network, print_beauty, utils,
 };
 
@@ -14,10 +16,32 @@ async fn main() -> std::io::Result<()> {
 
     let visit_url = format!("http://{}:{}", BIND_HOST, BIND_PORT);
 
-    let store: Keystore = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    // 设置 encrypted_keystore 时使用加密的 keystore，密码来自 keystore_passphrase_file 或者终端输入
+    let store: Keystore = match env::var("encrypted_keystore") {
+        Ok(path) => {
+            let passphrase_file = env::var("keystore_passphrase_file").ok();
+            let password = keystore::read_password(passphrase_file.as_deref()).unwrap();
+            Keystore::load_encrypted(&path, &password).unwrap()
+        }
+        Err(_) => Keystore::default(),
+    };
+    let account = store.account_from_env().unwrap();
     let network = network::from_env();
-    let client = client::default_client(network);
+    // 设置 backup_rpc (逗号分隔) 时，主节点被限流或者不可用会切换到备用节点
+    let mut client = match env::var("backup_rpc") {
+        Ok(urls) => client::failover_client(
+            network,
+            urls.split(',').map(|url| url.trim().to_string()).collect(),
+            SelectionPolicy::PrimaryBackup,
+        ),
+        Err(_) => client::default_client(network),
+    };
+    // 设置 expected_network 后，gateway 所在的链与之不一致时拒绝签名
+    if let Ok(expected) = env::var("expected_network") {
+        client
+            .enable_chain_guard(&expected.parse().unwrap())
+            .unwrap();
+    }
     let mut hook: HookCaller = HookCaller::new(
         Target::new(
             String::from("0x2b79486eaddff4fe262519e409214faefde25bcef88bac4f61a799a3d2e490bc"),
@@ -27,9 +51,13 @@ async fn main() -> std::io::Result<()> {
         account,
         client,
     );
+    // env gas_budget 可以是固定的数字、estimate 或者 estimate:<cap>，默认为 estimate
+    hook.set_gas_budget(gas::from_env());
 
     for _ in 1..=2 {
-        hook.call(vec![], vec![]).await;
+        if let Err(err) = hook.call(vec![], vec![]).await {
+            print_beauty!("hook call error : {}", err);
+        }
     }
 
     print_beauty!("now start an api hook server ... {}", visit_url);
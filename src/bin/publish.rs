@@ -1,15 +1,10 @@
-use sui_rust_operator::{
-    client,
-    keystore::Keystore,
-    network, print_beauty,
-    utils::{self, ADVISE_GAS_BUDGET},
-};
+use sui_rust_operator::{client, gas, keystore::Keystore, network, print_beauty, utils};
 
 #[tokio::main]
 async fn main() {
     let network = network::default();
     let store = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    let account = store.account_from_env().unwrap();
 
     print_beauty!("network : {}", network);
     print_beauty!("account : {}", account.to_address());
@@ -22,9 +17,10 @@ async fn main() {
     println!("dependencies : {:?}", compiled.dependencies);
 
     let myclient = client::debug_client(network);
+    let gas_budget = gas::from_env();
 
     let gas_object = match myclient
-        .get_avaliable_gas(account.to_address(), ADVISE_GAS_BUDGET)
+        .get_avaliable_gas(account.to_address(), gas_budget.probe())
         .await
     {
         Err(err) => {
@@ -36,33 +32,31 @@ async fn main() {
 
     assert!(gas_object.is_some());
 
+    let gas_object = gas_object.unwrap().coin_object_id;
     let pub_info = myclient
-        .unsafe_publish(
-            account.to_address(),
-            compiled.modules,
-            compiled.dependencies,
-            gas_object.unwrap().coin_object_id,
-            ADVISE_GAS_BUDGET,
-        )
+        .build_with_budget(gas_budget, |budget| {
+            myclient.unsafe_publish(
+                account.to_address(),
+                compiled.modules.clone(),
+                compiled.dependencies.clone(),
+                gas_object.clone(),
+                budget,
+            )
+        })
         .await
         .unwrap();
-    println!("{}", pub_info.result.tx_bytes);
+    println!("{}", pub_info.tx_bytes);
 
-    match pub_info
-        .result
-        .with_signed_execute(&myclient, &account)
-        .await
-    {
+    match pub_info.with_signed_execute(&myclient, &account).await {
         Ok(effect) => {
             print_beauty!(
                 "reuslt : {}",
                 serde_json::to_string_pretty(&effect).unwrap()
             );
 
-            print_beauty!(
-                "transaction link : {}",
-                myclient.network.transaction_link(&effect.result.digest)
-            );
+            if let Some(link) = myclient.network.transaction_link(&effect.digest) {
+                print_beauty!("transaction link : {}", link);
+            }
         }
         Err(err) => {
             print_beauty!("error : {}", err);
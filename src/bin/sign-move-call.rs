@@ -1,5 +1,5 @@
 use std::vec;
-use sui_rust_operator::{client, keystore::Keystore, network, payload::Payload};
+use sui_rust_operator::{client, gas, keystore::Keystore, network, payload::Payload};
 
 #[tokio::main]
 async fn main() {
@@ -9,7 +9,7 @@ async fn main() {
     let myclient = client::default_client(network);
 
     let store = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    let account = store.account_from_env().unwrap();
 
     let payload = Payload::move_call(
         account.to_address(),
@@ -19,7 +19,7 @@ async fn main() {
         vec![],
         vec![],
         "0x6abb224a86b8e571f221ea6bf6a5028923b29b13201a3c29f6fdaaaa3b4cbb97".to_string(),
-        3000_000,
+        3_000_000,
     );
 
     match myclient.send_payload(&payload).await {
@@ -32,33 +32,32 @@ async fn main() {
     };
 
     match myclient
-        .unsafe_move_call(
-            account.to_address(),
-            String::from("0x988fb71f38bb0323eeb5014c7a00e5988b047c09f39d58f157fc67d43ddfc091"),
-            "hello_world".to_string(),
-            "mint".to_string(),
-            vec![],
-            vec![],
-            "0x6abb224a86b8e571f221ea6bf6a5028923b29b13201a3c29f6fdaaaa3b4cbb97".to_string(),
-            3000_000,
-        )
+        .build_with_budget(gas::from_env(), |budget| {
+            myclient.unsafe_move_call(
+                account.to_address(),
+                String::from("0x988fb71f38bb0323eeb5014c7a00e5988b047c09f39d58f157fc67d43ddfc091"),
+                "hello_world".to_string(),
+                "mint".to_string(),
+                vec![],
+                vec![],
+                "0x6abb224a86b8e571f221ea6bf6a5028923b29b13201a3c29f6fdaaaa3b4cbb97".to_string(),
+                budget,
+            )
+        })
         .await
     {
         Err(err) => {
             println!("{}", err)
         }
         Ok(data) => {
-            let signed_payload = account.sign_unsafe_transaciton(&data.result);
+            let signed_payload = account.sign_unsafe_transaciton(&data);
 
             let effet = myclient.send_payload_effect(&signed_payload).await.unwrap();
 
             println!("reuslt : {}", serde_json::to_string_pretty(&effet).unwrap());
-            println!(
-                "transaction link : {}",
-                myclient
-                    .network
-                    .transaction_link(&effet.result.digest.to_string())
-            )
+            if let Some(link) = myclient.network.transaction_link(&effet.digest) {
+                println!("transaction link : {}", link)
+            }
         }
     }
 }
@@ -1,31 +1,33 @@
-use sui_rust_operator::{client, keystore::Keystore, network};
+use sui_rust_operator::{client, gas, keystore::Keystore, network};
 
 #[tokio::main]
 async fn main() {
     let network = network::default();
     let store = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    let account = store.account_from_env().unwrap();
 
     println!("gateway is : {}", network.get_gateway());
     println!("network is : {}", network);
     println!("address is : {}", account.to_address());
     let myclient = client::default_client(network);
 
-    let (object_id, gas_object, gas_budget, to_address) = (
+    let (object_id, gas_object, to_address) = (
         "0x104732c4b8961870be54b9d04c33cb54dfec72574c33aa0cce640e6dbfb56756",
         "0xcea9e5f61d0ea45058e90fae2b6422ebbbafb8c31ad01f263ec45b06e3eaf7df",
-        3000_000,
         account.to_address(),
     );
+    let owner_address = account.to_address();
 
     match myclient
-        .unsafe_transfer_object(
-            &account.to_address(),
-            object_id,
-            gas_object,
-            gas_budget,
-            &to_address,
-        )
+        .build_with_budget(gas::from_env(), |budget| {
+            myclient.unsafe_transfer_object(
+                &owner_address,
+                object_id,
+                gas_object,
+                budget,
+                &to_address,
+            )
+        })
         .await
     {
         Err(err) => {
@@ -37,19 +39,16 @@ async fn main() {
                 serde_json::to_string(&result).unwrap()
             );
 
-            let signed_transaction = account.sign_unsafe_transaciton(&result.result);
+            let signed_transaction = account.sign_unsafe_transaciton(&result);
             match myclient.send_payload_effect(&signed_transaction).await {
                 Err(err) => {
                     println!("signed result error : {}", err)
                 }
                 Ok(data) => {
                     println!("reuslt : {}", serde_json::to_string_pretty(&data).unwrap());
-                    println!(
-                        "transaction link : {}",
-                        myclient
-                            .network
-                            .transaction_link(&data.result.digest.to_string())
-                    )
+                    if let Some(link) = myclient.network.transaction_link(&data.digest) {
+                        println!("transaction link : {}", link)
+                    }
                 }
             }
         }
@@ -10,7 +10,7 @@ async fn main() {
     let myclient = client::default_client(network);
 
     let store = Keystore::default();
-    let account = store.load_account(0).unwrap();
+    let account = store.account_from_env().unwrap();
 
     let payload = Payload::move_call(
         account.to_address(),
@@ -20,7 +20,7 @@ async fn main() {
         vec![],
         vec![],
         "0x6abb224a86b8e571f221ea6bf6a5028923b29b13201a3c29f6fdaaaa3b4cbb97".to_string(),
-        3000_000,
+        3_000_000,
     );
 
     match myclient.send_payload(&payload).await {
@@ -41,7 +41,7 @@ async fn main() {
             vec![],
             vec![],
             "0x6abb224a86b8e571f221ea6bf6a5028923b29b13201a3c29f6fdaaaa3b4cbb97".to_string(),
-            3000_000,
+            3_000_000,
         )
         .await
     {
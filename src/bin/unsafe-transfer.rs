@@ -11,7 +11,7 @@ async fn main() {
         "0x0a27f6f7d3b7907fbcc4265ee8e63f5447312a8f53fb270a36f892e6f264008f",
         "0x104732c4b8961870be54b9d04c33cb54dfec72574c33aa0cce640e6dbfb56756",
         "0xcea9e5f61d0ea45058e90fae2b6422ebbbafb8c31ad01f263ec45b06e3eaf7df",
-        3000_000,
+        3_000_000,
         "0x0a27f6f7d3b7907fbcc4265ee8e63f5447312a8f53fb270a36f892e6f264008f",
     );
 
@@ -1,39 +1,186 @@
+use crate::endpoint::{RpcEndpoints, SelectionPolicy};
+use crate::error::SuiError;
+use crate::follower::{CheckpointStore, EventFollower};
+use crate::gas::{self, GasBudget};
 use crate::network::Network;
-use crate::payload::{self, FilterOption, Payload};
+use crate::pagination::{paginate, PageOptions};
+use crate::payload::{self, EventFilter, FilterOption, Payload, TransactionBlockResponseOptions};
 use crate::response::{
-    Balance, CoinInfo, CoinList, JsonResult, ObjectList, SimpleObject, TransactionEffectResult,
-    UnsafeTransactionResult,
+    Balance, CoinInfo, CoinList, DevInspectResult, DryRunResult, EventId, EventPage, JsonResult,
+    ObjectList, ObjectResponse, PastObjectResponse, RpcError, SimpleObject, SuiEvent, Supply,
+    TransactionBlockResponse, TransactionEffectResult, UnsafeTransactionResult,
 };
-use crate::utils::CustomErr;
+use crate::retry::RetryPolicy;
+use futures::{Stream, StreamExt};
 use reqwest::{self, Response};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{to_value, Value};
-use std::error::Error;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_BATCH_SIZE: usize = 50;
 
 pub struct Client {
     pub network: Network,
     debug: bool,
+    endpoints: RpcEndpoints,
+    // 设置后只向 chain identifier 一致的节点发送请求
+    expected_chain: Option<String>,
+    // 每个节点 url 对应的 chain identifier
+    endpoint_chains: Mutex<HashMap<String, String>>,
+    // 复用连接池，避免每次请求重新握手
+    http: reqwest::Client,
+    retry: RetryPolicy,
+    batch_size: usize,
 }
 
-pub fn debug_client(network: Network) -> Client {
-    Client {
-        network,
-        debug: true,
+// batch 请求中的单个返回，id 与请求的 payload id 对应
+#[derive(Deserialize)]
+struct BatchItem {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+fn build_http(connect_timeout: Duration, request_timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .unwrap()
+}
+
+async fn decode_response<T: DeserializeOwned>(resp: Response) -> Result<T, SuiError> {
+    let status = resp.status();
+    let body = resp.text().await?;
+    if !status.is_success() {
+        return Err(SuiError::HttpStatus {
+            status: status.as_u16(),
+            body,
+        });
+    }
+    let data: JsonResult<T> = serde_json::from_str(&body)?;
+    match (data.error, data.result) {
+        (Some(err), _) => Err(err.into()),
+        (None, Some(result)) => Ok(result),
+        // result 为 null 或者缺失时，只有 Option 等可以表示空值的类型才能解析
+        (None, None) => serde_json::from_value(Value::Null).map_err(|_| {
+            SuiError::Decode(format!("response has neither result nor error : {}", body))
+        }),
     }
 }
 
-pub fn default_client(network: Network) -> Client {
+fn new_client(network: Network, endpoints: RpcEndpoints, debug: bool) -> Client {
     Client {
         network,
-        debug: false,
+        debug,
+        endpoints,
+        expected_chain: None,
+        endpoint_chains: Mutex::new(HashMap::new()),
+        http: build_http(
+            Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS),
+            Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
+        ),
+        retry: RetryPolicy::default(),
+        batch_size: DEFAULT_BATCH_SIZE,
     }
 }
 
+pub fn debug_client(network: Network) -> Client {
+    let endpoints = RpcEndpoints::single(network.get_gateway());
+    new_client(network, endpoints, true)
+}
+
+pub fn default_client(network: Network) -> Client {
+    let endpoints = RpcEndpoints::single(network.get_gateway());
+    new_client(network, endpoints, false)
+}
+
+// network 的 gateway 作为第一个节点，backups 作为备用节点
+pub fn failover_client(network: Network, backups: Vec<String>, policy: SelectionPolicy) -> Client {
+    let endpoints = RpcEndpoints::new(network.get_gateway(), backups, policy);
+    new_client(network, endpoints, false)
+}
+
 impl Client {
     pub fn set_debug(&mut self) {
         self.debug = true;
     }
 
-    pub async fn get_faucet(&self, recipient: String) {
+    pub fn set_endpoints(&mut self, endpoints: RpcEndpoints) {
+        self.endpoints = endpoints;
+    }
+
+    pub fn set_timeouts(&mut self, connect_timeout: Duration, request_timeout: Duration) {
+        self.http = build_http(connect_timeout, request_timeout);
+    }
+
+    // 节点对单个 batch 的请求数量有限制
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size;
+    }
+
+    // 只对读请求生效，发送交易不会重试
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    pub fn endpoints(&self) -> &RpcEndpoints {
+        &self.endpoints
+    }
+
+    // expected 需要是 mainnet 或者 testnet 这类有固定 chain identifier 的网络
+    pub fn enable_chain_guard(&mut self, expected: &Network) -> Result<(), SuiError> {
+        match expected.chain_identifier() {
+            Some(chain_identifier) => {
+                self.expected_chain = Some(chain_identifier.to_string());
+                Ok(())
+            }
+            None => Err(SuiError::other(&format!(
+                "network {} has no known chain identifier",
+                expected.to_string()
+            ))),
+        }
+    }
+
+    // 本地网络或者私有网络可以直接指定 chain identifier
+    pub fn set_expected_chain(&mut self, chain_identifier: &str) {
+        self.expected_chain = Some(chain_identifier.to_string());
+    }
+
+    pub fn disable_chain_guard(&mut self) {
+        self.expected_chain = None;
+    }
+
+    pub async fn get_chain_identifier(&self) -> Result<String, SuiError> {
+        let payload = Payload::build("sui_getChainIdentifier".to_string(), vec![]);
+        self.read(&payload).await
+    }
+
+    // 未开启 chain guard 时直接通过。开启后 send_body 只会把请求发送到已经校验过的节点
+    pub async fn check_chain(&self) -> Result<(), SuiError> {
+        let expected = match &self.expected_chain {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        let actual = self.get_chain_identifier().await?;
+        if &actual != expected {
+            return Err(SuiError::ChainMismatch {
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    // 返回 faucet 接口的原始内容
+    pub async fn get_faucet(&self, recipient: String) -> Result<String, SuiError> {
         let info = payload::new_faucet(recipient);
         if self.debug {
             println!(
@@ -41,72 +188,229 @@ impl Client {
                 serde_json::to_string_pretty(&info).unwrap()
             );
         }
-        match self.network.faucet_url() {
-            Err(err) => {
-                println!("{}", err);
+        let url = self.network.faucet_url()?;
+        if self.debug {
+            println!("faucet url : {}", url);
+        }
+        let resp = self
+            .http
+            .post(url)
+            .header("Content-Type", "application/json")
+            .json(&info)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        if !status.is_success() {
+            return Err(SuiError::HttpStatus {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(body)
+    }
+
+    // 按照 endpoints 的顺序发送，连接失败或者 429 / 5xx 时切换到下一个节点
+    pub async fn send_payload(&self, payload: &Payload) -> Result<Response, SuiError> {
+        self.send_body(payload).await
+    }
+
+    // body 可以是单个 payload 或者 batch 请求的 payload 数组
+    async fn send_body<B: Serialize + ?Sized>(&self, body: &B) -> Result<Response, SuiError> {
+        if self.debug {
+            println!("send palyload : {}", serde_json::to_string(body).unwrap());
+        }
+        let mut last_err = SuiError::other("no rpc endpoint available");
+        for url in self.endpoints.candidates() {
+            // 开启 chain guard 时跳过 chain identifier 不一致的节点，签名的交易不会发送到其他链
+            if let Some(expected) = &self.expected_chain {
+                match self.endpoint_chain(&url).await {
+                    Ok(actual) if &actual == expected => {}
+                    Ok(actual) => {
+                        if self.debug {
+                            println!("rpc endpoint {} serves chain {}", url, actual);
+                        }
+                        last_err = SuiError::ChainMismatch {
+                            expected: expected.to_string(),
+                            actual,
+                        };
+                        continue;
+                    }
+                    Err(err) => {
+                        self.endpoints.report_failure(&url);
+                        last_err = err;
+                        continue;
+                    }
+                }
             }
-            Ok(url) => {
-                if self.debug {
-                    println!("faucet url : {}", url);
+            let start = Instant::now();
+            match self
+                .http
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(body)
+                .send()
+                .await
+            {
+                Err(err) => {
+                    if self.debug {
+                        println!("rpc endpoint {} failed : {}", url, err);
+                    }
+                    self.endpoints.report_failure(&url);
+                    last_err = SuiError::Transport(err);
+                }
+                Ok(resp) => {
+                    if self.debug {
+                        // chunked 返回没有 content-length
+                        println!(
+                            "endpoint : {} , status : {} , content-length: {:?} ",
+                            url,
+                            resp.status(),
+                            resp.content_length(),
+                        )
+                    }
+                    let status = resp.status();
+                    if status.as_u16() == 429 || status.is_server_error() {
+                        self.endpoints.report_failure(&url);
+                        last_err = SuiError::HttpStatus {
+                            status: status.as_u16(),
+                            body: resp.text().await.unwrap_or_default(),
+                        };
+                        continue;
+                    }
+                    self.endpoints.report_success(&url, start.elapsed());
+                    return Ok(resp);
                 }
-                let client = reqwest::Client::new();
-                let resp = client
-                    .post(url)
-                    .header("Content-Type", "application/json")
-                    .json(&info)
-                    .send()
-                    .await
-                    .unwrap();
-                println!("get faucet result : {}", resp.text().await.unwrap());
             }
         }
+        Err(last_err)
     }
 
-    pub async fn send_payload(&self, payload: &Payload) -> Result<Response, Box<dyn Error>> {
-        let client = reqwest::Client::new();
-        if self.debug {
-            println!(
-                "send palyload : {}",
-                serde_json::to_string(&payload).unwrap()
-            );
+    // 直接向 url 请求 chain identifier，不经过 endpoints 的选择，结果按照 url 缓存
+    async fn endpoint_chain(&self, url: &str) -> Result<String, SuiError> {
+        if let Some(chain) = self.endpoint_chains.lock().unwrap().get(url) {
+            return Ok(chain.to_string());
         }
-        match client
-            .post(self.network.get_gateway())
+        let payload = Payload::build("sui_getChainIdentifier".to_string(), vec![]);
+        let resp = self
+            .http
+            .post(url)
             .header("Content-Type", "application/json")
             .json(&payload)
             .send()
-            .await
-        {
-            Err(err) => Err(Box::new(err)),
-            Ok(resp) => {
-                if self.debug {
-                    println!(
-                        "status : {} , content-length: {} ",
-                        resp.status(),
-                        resp.content_length().unwrap(),
-                    )
+            .await?;
+        let chain: String = decode_response(resp).await?;
+        self.endpoint_chains
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), chain.to_string());
+        Ok(chain)
+    }
+
+    // 发送请求并解析 json rpc 的返回，rpc error 转换为 SuiError::Rpc
+    pub async fn request<T: DeserializeOwned>(&self, payload: &Payload) -> Result<T, SuiError> {
+        decode_response(self.send_payload(payload).await?).await
+    }
+
+    // 只读请求，网络错误或者 429 / 5xx 时按照 retry policy 重试
+    pub async fn read<T: DeserializeOwned>(&self, payload: &Payload) -> Result<T, SuiError> {
+        self.with_retry(|| self.request(payload)).await
+    }
+
+    async fn with_retry<T, F, Fut>(&self, f: F) -> Result<T, SuiError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, SuiError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(err) if err.is_retryable() && attempt < self.retry.max_retries => {
+                    let delay = self.retry.delay(attempt);
+                    if self.debug {
+                        println!("retry after {:?} : {}", delay, err);
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
                 }
-                Ok(resp)
+                result => return result,
             }
         }
     }
 
+    // 一次请求发送多个只读 payload，结果按照 payloads 的顺序返回
+    // 超过 batch_size 时拆分为多次请求
+    pub async fn batch<T: DeserializeOwned>(
+        &self,
+        payloads: &[Payload],
+    ) -> Result<Vec<Result<T, SuiError>>, SuiError> {
+        let mut ids = HashSet::new();
+        if !payloads.iter().all(|payload| ids.insert(payload.id())) {
+            return Err(SuiError::other("duplicate payload id in batch"));
+        }
+        let mut results = Vec::with_capacity(payloads.len());
+        for chunk in payloads.chunks(self.batch_size.max(1)) {
+            let mut items: HashMap<u64, BatchItem> = self
+                .with_retry(|| self.send_batch(chunk))
+                .await?
+                .into_iter()
+                .filter_map(|item| item.id.map(|id| (id, item)))
+                .collect();
+            for payload in chunk {
+                results.push(match items.remove(&payload.id()) {
+                    None => Err(SuiError::Decode(format!(
+                        "missing response for payload {}",
+                        payload.id()
+                    ))),
+                    Some(item) => match (item.error, item.result) {
+                        (Some(err), _) => Err(err.into()),
+                        (None, result) => serde_json::from_value(result.unwrap_or(Value::Null))
+                            .map_err(SuiError::from),
+                    },
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    async fn send_batch(&self, payloads: &[Payload]) -> Result<Vec<BatchItem>, SuiError> {
+        let resp = self.send_body(payloads).await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        if !status.is_success() {
+            return Err(SuiError::HttpStatus {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        // 整个 batch 失败时节点返回单个 error 对象
+        match serde_json::from_str::<Vec<BatchItem>>(&body) {
+            Ok(items) => Ok(items),
+            Err(err) => match serde_json::from_str::<BatchItem>(&body) {
+                Ok(BatchItem {
+                    error: Some(rpc_error),
+                    ..
+                }) => Err(rpc_error.into()),
+                _ => Err(err.into()),
+            },
+        }
+    }
+
+    // 交易执行失败时返回 SuiError::Execution
     pub async fn send_payload_effect(
         &self,
         payload: &Payload,
-    ) -> Result<JsonResult<TransactionEffectResult>, Box<dyn Error>> {
-        match self.send_payload(payload).await {
-            Ok(resp) => match resp.json::<JsonResult<TransactionEffectResult>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-            Err(err) => Err(err),
-            // Ok(resp) => {
-            //     let body_message = resp.text().await.unwrap();
-            //     println!("{}", body_message);
-            //     Err(CustomErr::new_box("msg"))
-            // }
+    ) -> Result<TransactionEffectResult, SuiError> {
+        let effect: TransactionEffectResult = self.request(payload).await?;
+        if let Some(effects) = &effect.effects {
+            if let Some(error) = effects.failure() {
+                return Err(SuiError::Execution {
+                    digest: effect.digest,
+                    error,
+                });
+            }
         }
+        Ok(effect)
     }
 
     pub async fn unsafe_transfer_object(
@@ -116,7 +420,7 @@ impl Client {
         gas_object: &str,
         gas_budget: u64,
         to_address: &str,
-    ) -> Result<JsonResult<UnsafeTransactionResult>, Box<dyn Error>> {
+    ) -> Result<UnsafeTransactionResult, SuiError> {
         let payload: Payload = Payload::unsafe_transfer_object(
             owner_address,
             object_id,
@@ -124,16 +428,10 @@ impl Client {
             gas_budget,
             to_address,
         );
-        let res = self.send_payload(&payload).await;
-        match res {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<UnsafeTransactionResult>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-        }
+        self.request(&payload).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn unsafe_move_call(
         &self,
         owner_address: String,
@@ -144,7 +442,7 @@ impl Client {
         arguments: Vec<Value>,
         gas_object: String,
         gas_budget: u64,
-    ) -> Result<JsonResult<UnsafeTransactionResult>, Box<dyn Error>> {
+    ) -> Result<UnsafeTransactionResult, SuiError> {
         let payload: Payload = Payload::move_call(
             owner_address,
             package_object_id,
@@ -155,15 +453,7 @@ impl Client {
             gas_object,
             gas_budget,
         );
-
-        let res = self.send_payload(&payload).await;
-        match res {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<UnsafeTransactionResult>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-        }
+        self.request(&payload).await
     }
 
     pub async fn unsafe_publish(
@@ -173,32 +463,136 @@ impl Client {
         dependencies: Vec<String>,
         gas_object: String,
         gas_budget: u64,
-    ) -> Result<JsonResult<UnsafeTransactionResult>, Box<dyn Error>> {
+    ) -> Result<UnsafeTransactionResult, SuiError> {
         let payload: Payload =
             Payload::publish(owner_address, modules, dependencies, gas_object, gas_budget);
+        self.request(&payload).await
+    }
 
-        let res = self.send_payload(&payload).await;
-        match res {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<UnsafeTransactionResult>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
+    // build 根据 gas budget 构建交易。估算时先用 probe budget 构建并 dry run，再用估算的 budget 重新构建
+    pub async fn build_with_budget<F, Fut>(
+        &self,
+        budget: GasBudget,
+        build: F,
+    ) -> Result<UnsafeTransactionResult, SuiError>
+    where
+        F: Fn(u64) -> Fut,
+        Fut: Future<Output = Result<UnsafeTransactionResult, SuiError>>,
+    {
+        let transaction = build(budget.probe()).await?;
+        let (margin, cap) = match budget {
+            GasBudget::Fixed(_) => return Ok(transaction),
+            GasBudget::Estimated { margin } => (margin, None),
+            GasBudget::Capped { margin, cap } => (margin, Some(cap)),
+        };
+        let dry_run = transaction.dry_run(self).await?;
+        if let Some(error) = dry_run.failure() {
+            return Err(SuiError::DryRun(error));
         }
+        let gas_used = dry_run
+            .gas_used()
+            .ok_or_else(|| SuiError::Decode("dry run returned no gas used".to_string()))?;
+        let rgp = self.get_reference_gas_price().await?;
+        // budget 为 cap 时 dry run 已经成功，估算值更大时使用 cap
+        let estimated = gas::estimate(gas_used, rgp, margin)?.min(cap.unwrap_or(u64::MAX));
+        if self.debug {
+            println!("estimated gas budget : {}", estimated);
+        }
+        build(estimated).await
     }
 
-    pub async fn get_object_id(
+    // 模拟执行 unsafe 接口返回的 tx_bytes，不需要签名
+    pub async fn dry_run_transaction_block(
         &self,
-        object_id: &String,
-    ) -> Result<JsonResult<SimpleObject>, Box<dyn Error>> {
+        tx_bytes: &str,
+    ) -> Result<DryRunResult, SuiError> {
+        self.read(&Payload::dry_run_transaction_block(tx_bytes))
+            .await
+    }
+
+    // 以 sender 身份执行 tx_kind 中的任意调用并返回每个命令的返回值，不会上链
+    pub async fn dev_inspect_transaction_block(
+        &self,
+        sender: &str,
+        tx_kind: &str,
+        gas_price: Option<u64>,
+        epoch: Option<u64>,
+    ) -> Result<DevInspectResult, SuiError> {
+        let payload = Payload::dev_inspect_transaction_block(sender, tx_kind, gas_price, epoch);
+        self.read(&payload).await
+    }
+
+    pub async fn get_object_id(&self, object_id: &String) -> Result<SimpleObject, SuiError> {
         let payload: Payload = Payload::sui_get_object(object_id, &FilterOption::default());
-        match self.send_payload(&payload).await {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<SimpleObject>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-        }
+        self.read(&payload).await
+    }
+
+    pub async fn get_transaction_block(
+        &self,
+        digest: &str,
+        options: &TransactionBlockResponseOptions,
+    ) -> Result<TransactionBlockResponse, SuiError> {
+        self.read(&Payload::get_transaction_block(digest, options))
+            .await
+    }
+
+    pub async fn multi_get_transaction_blocks(
+        &self,
+        digests: &[String],
+        options: &TransactionBlockResponseOptions,
+    ) -> Result<Vec<TransactionBlockResponse>, SuiError> {
+        self.read(&Payload::multi_get_transaction_blocks(digests, options))
+            .await
+    }
+
+    pub async fn multi_get_objects(
+        &self,
+        object_ids: &[String],
+        options: &FilterOption,
+    ) -> Result<Vec<ObjectResponse>, SuiError> {
+        self.read(&Payload::multi_get_objects(object_ids, options))
+            .await
+    }
+
+    pub async fn try_get_past_object(
+        &self,
+        object_id: &str,
+        version: u64,
+        options: &FilterOption,
+    ) -> Result<PastObjectResponse, SuiError> {
+        self.read(&Payload::try_get_past_object(object_id, version, options))
+            .await
+    }
+
+    pub async fn get_total_transaction_blocks(&self) -> Result<u64, SuiError> {
+        let total: String = self.read(&Payload::get_total_transaction_blocks()).await?;
+        total
+            .parse()
+            .map_err(|_| SuiError::Decode(format!("invalid total transaction blocks {}", total)))
+    }
+
+    pub async fn get_balance(
+        &self,
+        owner_address: &str,
+        coin_type: Option<&str>,
+    ) -> Result<Balance, SuiError> {
+        self.read(&Payload::get_balance(owner_address, coin_type))
+            .await
+    }
+
+    pub async fn get_reference_gas_price(&self) -> Result<u64, SuiError> {
+        let price: String = self.read(&Payload::get_reference_gas_price()).await?;
+        price
+            .parse()
+            .map_err(|_| SuiError::Decode(format!("invalid reference gas price {}", price)))
+    }
+
+    pub async fn get_total_supply(&self, coin_type: &str) -> Result<u64, SuiError> {
+        let supply: Supply = self.read(&Payload::get_total_supply(coin_type)).await?;
+        supply
+            .value
+            .parse()
+            .map_err(|_| SuiError::Decode(format!("invalid total supply {}", supply.value)))
     }
 
     pub async fn get_owned_objects(
@@ -207,7 +601,7 @@ impl Client {
         query: payload::QueryOption,
         cursor: Option<String>,
         limit: Option<u64>,
-    ) -> Result<JsonResult<ObjectList>, Box<dyn Error>> {
+    ) -> Result<ObjectList, SuiError> {
         let payload = Payload::build(
             String::from("suix_getOwnedObjects"),
             vec![
@@ -223,31 +617,15 @@ impl Client {
                 },
             ],
         );
-        match self.send_payload(&payload).await {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<ObjectList>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-        }
+        self.read(&payload).await
     }
 
-    pub async fn get_all_balances(
-        &self,
-        owner_address: String,
-    ) -> Result<JsonResult<Vec<Balance>>, Box<dyn Error>> {
+    pub async fn get_all_balances(&self, owner_address: String) -> Result<Vec<Balance>, SuiError> {
         let payload = Payload::build(
             "suix_getAllBalances".to_string(),
             vec![Value::String(owner_address)],
         );
-
-        match self.send_payload(&payload).await {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<Vec<Balance>>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
-            },
-        }
+        self.read(&payload).await
     }
 
     pub async fn get_all_coins(
@@ -256,7 +634,7 @@ impl Client {
         coin_type: String,
         cursor: Option<String>,
         limit: Option<u64>,
-    ) -> Result<JsonResult<CoinList>, Box<dyn Error>> {
+    ) -> Result<CoinList, SuiError> {
         let payload = Payload::build(
             "suix_getCoins".to_string(),
             vec![
@@ -272,39 +650,529 @@ impl Client {
                 },
             ],
         );
+        self.read(&payload).await
+    }
 
-        match self.send_payload(&payload).await {
-            Err(err) => Err(err),
-            Ok(resp) => match resp.json::<JsonResult<CoinList>>().await {
-                Err(err) => Err(Box::new(err)),
-                Ok(json_object) => Ok(json_object),
+    pub async fn get_gas_list(&self, owner_address: String) -> Result<CoinList, SuiError> {
+        self.get_all_coins(owner_address, "0x2::sui::SUI".to_string(), None, None)
+            .await
+    }
+
+    pub fn owned_objects_stream(
+        &self,
+        owner_address: String,
+        query: payload::QueryOption,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<SimpleObject, SuiError>> + '_ {
+        paginate(
+            move |cursor, limit| {
+                self.get_owned_objects(owner_address.clone(), query.clone(), cursor, limit)
             },
-        }
+            options,
+        )
     }
 
-    pub async fn get_gas_list(
+    pub fn coins_stream(
         &self,
         owner_address: String,
-    ) -> Result<JsonResult<CoinList>, Box<dyn Error>> {
-        self.get_all_coins(owner_address, "0x2::sui::SUI".to_string(), None, None)
-            .await
+        coin_type: String,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<CoinInfo, SuiError>> + '_ {
+        paginate(
+            move |cursor, limit| {
+                self.get_all_coins(owner_address.clone(), coin_type.clone(), cursor, limit)
+            },
+            options,
+        )
+    }
+
+    pub async fn query_events(
+        &self,
+        filter: &EventFilter,
+        cursor: Option<EventId>,
+        limit: Option<u64>,
+        descending: bool,
+    ) -> Result<EventPage, SuiError> {
+        self.read(&Payload::query_events(
+            filter,
+            cursor.as_ref(),
+            limit,
+            descending,
+        ))
+        .await
+    }
+
+    pub fn events_stream(
+        &self,
+        filter: EventFilter,
+        descending: bool,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<SuiEvent, SuiError>> + '_ {
+        paginate(
+            move |cursor, limit| {
+                let payload = Payload::query_events(&filter, cursor.as_ref(), limit, descending);
+                async move { self.read::<EventPage>(&payload).await }
+            },
+            options,
+        )
+    }
+
+    // 从 store 中保存的 cursor 之后开始轮询 event
+    pub fn event_follower<S: CheckpointStore>(
+        &self,
+        filter: EventFilter,
+        store: S,
+    ) -> Result<EventFollower<'_, S>, SuiError> {
+        EventFollower::new(self, filter, store)
     }
 
+    // 遍历所有的 SUI coin，返回第一个余额大于 amount 的
     pub async fn get_avaliable_gas(
         &self,
         owner_address: String,
         amount: u64,
-    ) -> Result<CoinInfo, Box<dyn Error>> {
-        match self.get_gas_list(owner_address).await {
-            Err(err) => Err(err),
-            Ok(data) => {
-                for coin in data.result.data {
-                    if coin.balance_u64() > amount {
-                        return Ok(coin);
+    ) -> Result<CoinInfo, SuiError> {
+        let coins = self.coins_stream(
+            owner_address,
+            "0x2::sui::SUI".to_string(),
+            PageOptions::default(),
+        );
+        futures::pin_mut!(coins);
+        while let Some(coin) = coins.next().await {
+            let coin = coin?;
+            if coin.balance_u64() > amount {
+                return Ok(coin);
+            }
+        }
+        Err(SuiError::NotFound(format!(
+            "gas coin with balance more than {}",
+            amount
+        )))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::account::SuiAccount;
+    use crate::network::{MAINNET_CHAIN_IDENTIFIER, TESTNET_CHAIN_IDENTIFIER};
+    use crate::utils::{base64_decode, base64_encode};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 本地 json rpc 服务，handler 根据请求的 json 返回 result
+    pub(crate) async fn mock_rpc<F>(handler: F) -> String
+    where
+        F: Fn(Value) -> Value + Send + Sync + 'static,
+    {
+        mock_rpc_status(move |request| (200, handler(request))).await
+    }
+
+    // handler 同时返回 http status
+    pub(crate) async fn mock_rpc_status<F>(handler: F) -> String
+    where
+        F: Fn(Value) -> (u16, Value) + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handler = std::sync::Arc::new(handler);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![];
+                    let mut chunk = [0u8; 4096];
+                    let body = loop {
+                        let n = socket.read(&mut chunk).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        let text = String::from_utf8_lossy(&buf).to_string();
+                        if let Some(pos) = text.find("\r\n\r\n") {
+                            let length = text[..pos]
+                                .lines()
+                                .find_map(|line| {
+                                    line.to_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap())
+                                })
+                                .unwrap_or(0);
+                            if buf.len() >= pos + 4 + length {
+                                break buf[pos + 4..pos + 4 + length].to_vec();
+                            }
+                        }
+                    };
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+                    let (status, response) = handler(request);
+                    let response = response.to_string();
+                    let head = format!(
+                        "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        response.len()
+                    );
+                    socket.write_all(head.as_bytes()).await.unwrap();
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_chain_guard() {
+        let url = mock_rpc(|request| {
+            assert_eq!(request["method"], "sui_getChainIdentifier");
+            serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": MAINNET_CHAIN_IDENTIFIER})
+        })
+        .await;
+        let mut client = default_client(Network::Custom(url));
+        assert!(client.check_chain().await.is_ok());
+
+        client.enable_chain_guard(&Network::Mainnet).unwrap();
+        assert!(client.check_chain().await.is_ok());
+
+        client.enable_chain_guard(&Network::Testnet).unwrap();
+        assert!(matches!(
+            client.check_chain().await,
+            Err(SuiError::ChainMismatch { .. })
+        ));
+        assert!(client.enable_chain_guard(&Network::Devnet).is_err());
+    }
+
+    // 两个节点属于不同的链，RoundRobin 时请求也只发送到 chain identifier 一致的节点
+    #[tokio::test]
+    async fn test_chain_guard_per_endpoint() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let mock = |chain: &'static str, executes: Arc<AtomicUsize>| {
+            mock_rpc(move |request| {
+                let result = match request["method"].as_str().unwrap() {
+                    "sui_getChainIdentifier" => serde_json::json!(chain),
+                    _ => {
+                        executes.fetch_add(1, Ordering::SeqCst);
+                        serde_json::json!({"digest": "abc"})
+                    }
+                };
+                serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+            })
+        };
+        let mainnet_executes = Arc::new(AtomicUsize::new(0));
+        let testnet_executes = Arc::new(AtomicUsize::new(0));
+        let mainnet = mock(MAINNET_CHAIN_IDENTIFIER, mainnet_executes.clone()).await;
+        let testnet = mock(TESTNET_CHAIN_IDENTIFIER, testnet_executes.clone()).await;
+
+        let mut client = failover_client(
+            Network::Custom(testnet),
+            vec![mainnet],
+            SelectionPolicy::RoundRobin,
+        );
+        client.enable_chain_guard(&Network::Mainnet).unwrap();
+        client.check_chain().await.unwrap();
+        let payload = Payload::safe_transaction_block_payload("AAA=", "sig");
+        for _ in 0..4 {
+            let result: Value = client.request(&payload).await.unwrap();
+            assert_eq!(result["digest"], "abc");
+        }
+        assert_eq!(mainnet_executes.load(Ordering::SeqCst), 4);
+        assert_eq!(testnet_executes.load(Ordering::SeqCst), 0);
+
+        client.enable_chain_guard(&Network::Testnet).unwrap();
+        client.request::<Value>(&payload).await.unwrap();
+        assert_eq!(testnet_executes.load(Ordering::SeqCst), 1);
+
+        client.set_expected_chain("00000000");
+        assert!(matches!(
+            client.request::<Value>(&payload).await,
+            Err(SuiError::ChainMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_and_execution_error() {
+        let url = mock_rpc(|request| match request["method"].as_str().unwrap() {
+            "suix_getAllBalances" => serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": {"code": -32602, "message": "Invalid params", "data": "bad address"}
+            }),
+            "unsafe_moveCall" => serde_json::json!({"jsonrpc": "2.0", "id": request["id"]}),
+            _ => serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "digest": "abc",
+                    "events": [],
+                    "rawTransaction": "",
+                    "transaction": null,
+                    "effects": {
+                        "status": {"status": "failure", "error": "MoveAbort(1) in command 0"},
+                        "executedEpoch": "1",
+                        "messageVersion": "v1",
+                        "dependencies": [],
+                        "transactionDigest": "abc",
+                        "gasObject": {"owner": "Immutable", "reference": {"objectId": "0x1", "version": 1, "digest": "d"}},
+                        "modifiedAtVersions": [],
+                        "gasUsed": {"computationCost": "1", "storageCost": "1", "storageRebate": "0", "nonRefundableStorageFee": "0"},
+                        "mutated": []
                     }
                 }
-                Err(CustomErr::new_box("coin not found"))
+            }),
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        match client.get_all_balances("0x1".to_string()).await {
+            Err(SuiError::Rpc { code, data, .. }) => {
+                assert_eq!(code, -32602);
+                assert_eq!(data.unwrap(), "bad address");
             }
+            _ => panic!("expect rpc error"),
+        }
+        // 没有 result 也没有 error 时不会返回空的 tx_bytes
+        let payload = Payload::move_call(
+            "0x1".to_string(),
+            "0x5".to_string(),
+            "hello_world".to_string(),
+            "mint".to_string(),
+            vec![],
+            vec![],
+            "0x6".to_string(),
+            1000,
+        );
+        assert!(matches!(
+            client.request::<UnsafeTransactionResult>(&payload).await,
+            Err(SuiError::Decode(_))
+        ));
+        assert!(client
+            .request::<Option<String>>(&payload)
+            .await
+            .unwrap()
+            .is_none());
+        let payload = Payload::safe_transaction_block_payload("AAA=", "sig");
+        match client.send_payload_effect(&payload).await {
+            Err(SuiError::Execution { digest, error }) => {
+                assert_eq!(digest, "abc");
+                assert!(error.contains("MoveAbort"));
+            }
+            _ => panic!("expect execution error"),
+        }
+    }
+
+    // dry run 失败时不签名，也不会发送 sui_executeTransactionBlock
+    #[tokio::test]
+    async fn test_dry_run_before_sign() {
+        let url = mock_rpc(|request| {
+            assert_eq!(request["method"], "sui_dryRunTransactionBlock");
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "effects": {
+                        "status": {"status": "failure", "error": "MoveAbort(2) in command 0"},
+                        "executedEpoch": "1",
+                        "messageVersion": "v1",
+                        "transactionDigest": "abc",
+                        "gasObject": {"owner": "Immutable", "reference": {"objectId": "0x1", "version": 1, "digest": "d"}},
+                        "gasUsed": {"computationCost": "1000", "storageCost": "2000", "storageRebate": "500", "nonRefundableStorageFee": "0"}
+                    },
+                    "events": [],
+                    "objectChanges": [],
+                    "balanceChanges": [],
+                    "input": {}
+                }
+            })
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        let mut transaction: UnsafeTransactionResult = serde_json::from_value(
+            serde_json::json!({"txBytes": "AAA=", "gas": [], "inputObjects": []}),
+        )
+        .unwrap();
+        let dry_run = transaction.dry_run(&client).await.unwrap();
+        assert_eq!(dry_run.gas_used().unwrap().storage_rebate, "500");
+
+        transaction.set_dry_run();
+        let account = SuiAccount::new_account();
+        match transaction.with_signed_execute(&client, &account).await {
+            Err(SuiError::DryRun(error)) => assert_eq!(error, "MoveAbort(2) in command 0"),
+            _ => panic!("expect dry run error"),
         }
     }
+
+    // tx_bytes 中保存构建时使用的 gas budget
+    #[tokio::test]
+    async fn test_build_with_budget() {
+        let url = mock_rpc(|request| {
+            let result = match request["method"].as_str().unwrap() {
+                "unsafe_moveCall" => serde_json::json!({
+                    "txBytes": base64_encode(request["params"][7].as_str().unwrap().as_bytes()),
+                    "gas": [],
+                    "inputObjects": []
+                }),
+                // 调用修改了 shared object
+                "sui_dryRunTransactionBlock" => serde_json::json!({
+                    "effects": {
+                        "status": {"status": "success"},
+                        "executedEpoch": "1",
+                        "messageVersion": "v1",
+                        "transactionDigest": "abc",
+                        "gasObject": {"owner": {"AddressOwner": "0x6"}, "reference": {"objectId": "0x1", "version": 1, "digest": "d"}},
+                        "gasUsed": {"computationCost": "1000", "storageCost": "2000", "storageRebate": "500", "nonRefundableStorageFee": "0"},
+                        "sharedObjects": [{"objectId": "0x9", "version": 1, "digest": "e"}],
+                        "mutated": [
+                            {"owner": {"Shared": {"initial_shared_version": 1}}, "reference": {"objectId": "0x9", "version": 2, "digest": "f"}},
+                            {"owner": {"AddressOwner": "0x6"}, "reference": {"objectId": "0x1", "version": 2, "digest": "g"}}
+                        ]
+                    }
+                }),
+                _ => serde_json::json!("750"),
+            };
+            serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 750);
+
+        let build = |budget| {
+            client.unsafe_move_call(
+                "0x1".to_string(),
+                "0x5".to_string(),
+                "hello_world".to_string(),
+                "mint".to_string(),
+                vec![],
+                vec![],
+                "0x6".to_string(),
+                budget,
+            )
+        };
+        let budget_of = |transaction: UnsafeTransactionResult| {
+            String::from_utf8(base64_decode(&transaction.tx_bytes).unwrap()).unwrap()
+        };
+        let fixed = client.build_with_budget(GasBudget::Fixed(5000), build);
+        assert_eq!(budget_of(fixed.await.unwrap()), "5000");
+        let estimated = client.build_with_budget(GasBudget::estimated(), build);
+        assert_eq!(budget_of(estimated.await.unwrap()), "903000");
+        let capped = client.build_with_budget(GasBudget::capped(1000000), build);
+        assert_eq!(budget_of(capped.await.unwrap()), "903000");
+        let capped = client.build_with_budget(GasBudget::capped(2000), build);
+        assert_eq!(budget_of(capped.await.unwrap()), "2000");
+    }
+
+    #[tokio::test]
+    async fn test_retry_read_requests() {
+        let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = count.clone();
+        let url = mock_rpc_status(move |request| {
+            let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if n < 2 {
+                return (503, Value::Null);
+            }
+            (
+                200,
+                serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": "4c78adac"}),
+            )
+        })
+        .await;
+        let mut client = default_client(Network::Custom(url));
+        client.set_retry_policy(RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        });
+        assert_eq!(client.get_chain_identifier().await.unwrap(), "4c78adac");
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // 发送交易不会重试
+        count.store(0, std::sync::atomic::Ordering::SeqCst);
+        let payload = Payload::safe_transaction_block_payload("AAA=", "sig");
+        assert!(matches!(
+            client.send_payload_effect(&payload).await,
+            Err(SuiError::HttpStatus { status: 503, .. })
+        ));
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let url = mock_rpc(|request| {
+            // 倒序返回，第二个请求返回 rpc error
+            let items: Vec<Value> = request
+                .as_array()
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|item| match item["params"][0].as_str().unwrap() {
+                    "0x2" => serde_json::json!({"jsonrpc": "2.0", "id": item["id"], "error": {"code": -32602, "message": "bad"}}),
+                    owner => serde_json::json!({"jsonrpc": "2.0", "id": item["id"], "result": [{
+                        "coinType": "0x2::sui::SUI",
+                        "coinObjectCount": 1,
+                        "totalBalance": owner,
+                        "lockedBalance": {}
+                    }]}),
+                })
+                .collect();
+            Value::Array(items)
+        })
+        .await;
+        let mut client = default_client(Network::Custom(url));
+        client.set_batch_size(2);
+        let payloads: Vec<Payload> = ["0x1", "0x2", "0x3"]
+            .iter()
+            .map(|owner| {
+                Payload::build(
+                    "suix_getAllBalances".to_string(),
+                    vec![Value::String(owner.to_string())],
+                )
+            })
+            .collect();
+        let results = client.batch::<Vec<Balance>>(&payloads).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap()[0].total_balance, "0x1");
+        assert!(matches!(
+            results[1],
+            Err(SuiError::Rpc { code: -32602, .. })
+        ));
+        assert_eq!(results[2].as_ref().unwrap()[0].total_balance, "0x3");
+    }
+
+    #[tokio::test]
+    async fn test_gas_on_second_page() {
+        let url = mock_rpc(|request| {
+            let coin = |id: &str, balance: &str| {
+                serde_json::json!({
+                    "coinType": "0x2::sui::SUI",
+                    "coinObjectId": id,
+                    "version": "1",
+                    "digest": "d",
+                    "balance": balance,
+                    "previousTransaction": "t"
+                })
+            };
+            let result = match request["params"][2].as_str() {
+                None => serde_json::json!({
+                    "data": [coin("0x1", "10"), coin("0x2", "20")],
+                    "nextCursor": "0x2",
+                    "hasNextPage": true
+                }),
+                Some(_) => serde_json::json!({
+                    "data": [coin("0x3", "5000")],
+                    "nextCursor": "0x3",
+                    "hasNextPage": false
+                }),
+            };
+            serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        let gas = client
+            .get_avaliable_gas("0x1".to_string(), 1000)
+            .await
+            .unwrap();
+        assert_eq!(gas.coin_object_id, "0x3");
+        assert!(matches!(
+            client.get_avaliable_gas("0x1".to_string(), 10_000).await,
+            Err(SuiError::NotFound(_))
+        ));
+    }
 }
@@ -0,0 +1,153 @@
+use crate::{account::SuiAccount, keystore::Keystore, network::Network, utils::CustomErr};
+use serde::{Deserialize, Serialize};
+use std::{env, error::Error, fs, path::Path};
+
+// sui cli 的配置文件 ~/.sui/sui_config/client.yaml
+#[derive(Serialize, Deserialize)]
+pub struct ClientConfig {
+    // sui cli 写入的是 `File: path` 的形式
+    #[serde(with = "serde_yaml::with::singleton_map")]
+    pub keystore: KeystoreLocation,
+    #[serde(default)]
+    pub envs: Vec<SuiEnv>,
+    pub active_env: Option<String>,
+    pub active_address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum KeystoreLocation {
+    File(String),
+    InMem(serde_yaml::Value),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SuiEnv {
+    pub alias: String,
+    pub rpc: String,
+    pub ws: Option<String>,
+}
+
+impl SuiEnv {
+    pub fn network(&self) -> Network {
+        Network::from_rpc(&self.rpc)
+    }
+
+    // 优先使用配置的 ws ，未配置时由 rpc 地址推导
+    pub fn ws_url(&self) -> String {
+        match &self.ws {
+            Some(ws) => ws.to_string(),
+            None => self.network().get_ws_url(),
+        }
+    }
+}
+
+pub fn default_config_path() -> String {
+    format!("{}/.sui/sui_config/client.yaml", env::var("HOME").unwrap())
+}
+
+impl ClientConfig {
+    pub fn load_from(path: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&contents)?)
+    }
+
+    // client.yaml 不存在或者无法解析时返回 None
+    pub fn load_default() -> Option<Self> {
+        let path = default_config_path();
+        if !Path::new(&path).exists() {
+            return None;
+        }
+        Self::load_from(&path).ok()
+    }
+
+    pub fn get_env(&self, alias: &str) -> Option<&SuiEnv> {
+        self.envs.iter().find(|item| item.alias == alias)
+    }
+
+    pub fn active_env(&self) -> Option<&SuiEnv> {
+        match &self.active_env {
+            Some(alias) => self.get_env(alias),
+            None => None,
+        }
+    }
+
+    pub fn network(&self) -> Result<Network, Box<dyn Error>> {
+        match self.active_env() {
+            Some(sui_env) => Ok(sui_env.network()),
+            None => Err(CustomErr::new_box("active env not found in client.yaml")),
+        }
+    }
+
+    pub fn ws_url(&self) -> Result<String, Box<dyn Error>> {
+        match self.active_env() {
+            Some(sui_env) => Ok(sui_env.ws_url()),
+            None => Err(CustomErr::new_box("active env not found in client.yaml")),
+        }
+    }
+
+    pub fn keystore_path(&self) -> Option<String> {
+        match &self.keystore {
+            KeystoreLocation::File(path) => Some(path.to_string()),
+            KeystoreLocation::InMem(_) => None,
+        }
+    }
+
+    pub fn keystore(&self) -> Result<Keystore, Box<dyn Error>> {
+        match self.keystore_path() {
+            Some(path) => Ok(Keystore::load_from(&path)),
+            None => Err(CustomErr::new_box("in-memory keystore is not supported")),
+        }
+    }
+
+    pub fn active_account(&self) -> Result<SuiAccount, Box<dyn Error>> {
+        match &self.active_address {
+            Some(address) => self.keystore()?.find_by_address(address),
+            None => Err(CustomErr::new_box(
+                "active address not found in client.yaml",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_client_config() {
+        let config: ClientConfig = serde_yaml::from_str(
+            r#"---
+keystore:
+  File: /home/sui/.sui/sui_config/sui.keystore
+envs:
+  - alias: testnet
+    rpc: "https://fullnode.testnet.sui.io:443"
+    ws: ~
+  - alias: local
+    rpc: "http://127.0.0.1:9000"
+    ws: "ws://127.0.0.1:9001"
+    basic_auth: ~
+active_env: local
+active_address: "0x0a27f6f7d3b7907fbcc4265ee8e63f5447312a8f53fb270a36f892e6f264008f"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.keystore_path().unwrap(),
+            "/home/sui/.sui/sui_config/sui.keystore"
+        );
+        assert_eq!(
+            config.network().unwrap().get_gateway(),
+            "http://127.0.0.1:9000"
+        );
+        assert_eq!(
+            config.get_env("testnet").unwrap().network().to_string(),
+            "testnet"
+        );
+        assert_eq!(config.ws_url().unwrap(), "ws://127.0.0.1:9001");
+        assert_eq!(
+            config.get_env("testnet").unwrap().ws_url(),
+            "wss://fullnode.testnet.sui.io:443"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+const DEFAULT_COOLDOWN_SECS: u64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionPolicy {
+    // 按顺序使用，第一个不可用时使用后面的备份
+    PrimaryBackup,
+    RoundRobin,
+    // 优先使用平均延迟最低的节点
+    LowestLatency,
+}
+
+#[derive(Default)]
+struct Health {
+    failures: u64,
+    cooldown_until: Option<Instant>,
+    latency_ms: Option<f64>,
+}
+
+struct Endpoint {
+    url: String,
+    health: Mutex<Health>,
+}
+
+pub struct EndpointStatus {
+    pub url: String,
+    // 连续失败次数
+    pub failures: u64,
+    pub available: bool,
+    pub latency_ms: Option<f64>,
+}
+
+pub struct RpcEndpoints {
+    endpoints: Vec<Endpoint>,
+    policy: SelectionPolicy,
+    cooldown: Duration,
+    next: AtomicUsize,
+}
+
+impl RpcEndpoints {
+    // primary 单独传入，节点列表不会为空
+    pub fn new(primary: String, backups: Vec<String>, policy: SelectionPolicy) -> Self {
+        Self {
+            endpoints: std::iter::once(primary)
+                .chain(backups)
+                .map(|url| Endpoint {
+                    url,
+                    health: Mutex::new(Health::default()),
+                })
+                .collect(),
+            policy,
+            cooldown: Duration::from_secs(DEFAULT_COOLDOWN_SECS),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn single(url: String) -> Self {
+        Self::new(url, vec![], SelectionPolicy::PrimaryBackup)
+    }
+
+    // 请求失败的节点在 cooldown 时间内排到最后
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn policy(&self) -> SelectionPolicy {
+        self.policy
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.endpoints.iter().map(|item| item.url.clone()).collect()
+    }
+
+    pub fn primary(&self) -> String {
+        self.endpoints[0].url.clone()
+    }
+
+    // 按照 policy 返回本次请求尝试的顺序，冷却中的节点放在最后作为兜底
+    pub fn candidates(&self) -> Vec<String> {
+        let len = self.endpoints.len();
+        let mut order: Vec<usize> = (0..len).collect();
+        match self.policy {
+            SelectionPolicy::PrimaryBackup => {}
+            SelectionPolicy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+                order.rotate_left(start);
+            }
+            SelectionPolicy::LowestLatency => {
+                // 还没有延迟数据的节点优先探测
+                order.sort_by(|a, b| {
+                    let latency = |idx: &usize| {
+                        self.endpoints[*idx]
+                            .health
+                            .lock()
+                            .unwrap()
+                            .latency_ms
+                            .unwrap_or(0.0)
+                    };
+                    latency(a).total_cmp(&latency(b))
+                });
+            }
+        }
+        let now = Instant::now();
+        let (healthy, cooling): (Vec<usize>, Vec<usize>) = order.into_iter().partition(|idx| {
+            match self.endpoints[*idx].health.lock().unwrap().cooldown_until {
+                Some(until) => until <= now,
+                None => true,
+            }
+        });
+        healthy
+            .into_iter()
+            .chain(cooling)
+            .map(|idx| self.endpoints[idx].url.clone())
+            .collect()
+    }
+
+    fn find(&self, url: &str) -> Option<&Endpoint> {
+        self.endpoints.iter().find(|item| item.url == url)
+    }
+
+    pub fn report_success(&self, url: &str, latency: Duration) {
+        if let Some(endpoint) = self.find(url) {
+            let mut health = endpoint.health.lock().unwrap();
+            let latency_ms = latency.as_secs_f64() * 1000.0;
+            health.latency_ms = Some(match health.latency_ms {
+                Some(avg) => avg * 0.8 + latency_ms * 0.2,
+                None => latency_ms,
+            });
+            health.failures = 0;
+            health.cooldown_until = None;
+        }
+    }
+
+    pub fn report_failure(&self, url: &str) {
+        if let Some(endpoint) = self.find(url) {
+            let mut health = endpoint.health.lock().unwrap();
+            health.failures += 1;
+            health.cooldown_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|item| {
+                let health = item.health.lock().unwrap();
+                EndpointStatus {
+                    url: item.url.clone(),
+                    failures: health.failures,
+                    available: health
+                        .cooldown_until
+                        .map(|until| until <= now)
+                        .unwrap_or(true),
+                    latency_ms: health.latency_ms,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string(), "c".to_string()]
+    }
+
+    fn three_endpoints(policy: SelectionPolicy) -> RpcEndpoints {
+        RpcEndpoints::new("a".to_string(), urls()[1..].to_vec(), policy)
+    }
+
+    #[test]
+    fn test_primary_backup_cooldown() {
+        let endpoints = three_endpoints(SelectionPolicy::PrimaryBackup);
+        assert_eq!(endpoints.candidates(), urls());
+        endpoints.report_failure("a");
+        assert_eq!(endpoints.candidates(), vec!["b", "c", "a"]);
+        endpoints.report_success("a", Duration::from_millis(10));
+        assert_eq!(endpoints.candidates(), urls());
+
+        let endpoints =
+            three_endpoints(SelectionPolicy::PrimaryBackup).with_cooldown(Duration::from_millis(0));
+        endpoints.report_failure("a");
+        assert_eq!(endpoints.candidates(), urls());
+    }
+
+    #[test]
+    fn test_round_robin_and_latency() {
+        let endpoints = three_endpoints(SelectionPolicy::RoundRobin);
+        assert_eq!(endpoints.candidates()[0], "a");
+        assert_eq!(endpoints.candidates()[0], "b");
+        assert_eq!(endpoints.candidates()[0], "c");
+        assert_eq!(endpoints.candidates()[0], "a");
+
+        let endpoints = three_endpoints(SelectionPolicy::LowestLatency);
+        endpoints.report_success("a", Duration::from_millis(300));
+        endpoints.report_success("b", Duration::from_millis(20));
+        endpoints.report_success("c", Duration::from_millis(80));
+        assert_eq!(endpoints.candidates(), vec!["b", "c", "a"]);
+
+        let endpoints = RpcEndpoints::new("a".to_string(), vec![], SelectionPolicy::RoundRobin);
+        assert_eq!(endpoints.candidates(), vec!["a"]);
+        assert_eq!(endpoints.primary(), "a");
+    }
+}
@@ -0,0 +1,141 @@
+use crate::response::RpcError;
+use serde_json::Value;
+use std::{error::Error, fmt::Display};
+
+// client 和 response 层统一的错误类型
+#[derive(Debug)]
+pub enum SuiError {
+    // 连接失败、超时等网络错误
+    Transport(reqwest::Error),
+    HttpStatus {
+        status: u16,
+        body: String,
+    },
+    // 返回内容无法解析为预期的结构
+    Decode(String),
+    Rpc {
+        code: i32,
+        message: String,
+        data: Option<Value>,
+    },
+    // 交易已经上链，但是执行失败，例如 move abort
+    Execution {
+        digest: String,
+        error: String,
+    },
+    Signing(String),
+    // dry run 失败，交易没有签名和发送
+    DryRun(String),
+    // websocket 连接或者订阅失败
+    WebSocket(String),
+    ChainMismatch {
+        expected: String,
+        actual: String,
+    },
+    NotFound(String),
+    // checkpoint 等本地存储的读写错误
+    Storage(String),
+    Other(String),
+}
+
+impl SuiError {
+    pub fn other(msg: &str) -> Self {
+        SuiError::Other(msg.to_string())
+    }
+
+    // 429 和 5xx 以及网络错误可以重试
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SuiError::Transport(_) | SuiError::WebSocket(_) => true,
+            SuiError::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl Display for SuiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuiError::Transport(err) => write!(f, "transport error : {}", err),
+            SuiError::HttpStatus { status, body } => {
+                write!(f, "http status {} : {}", status, body)
+            }
+            SuiError::Decode(msg) => write!(f, "decode error : {}", msg),
+            SuiError::Rpc {
+                code,
+                message,
+                data,
+            } => match data {
+                Some(data) => write!(f, "rpc error {} : {} , data : {}", code, message, data),
+                None => write!(f, "rpc error {} : {}", code, message),
+            },
+            SuiError::Execution { digest, error } => {
+                write!(f, "transaction {} execution failed : {}", digest, error)
+            }
+            SuiError::Signing(msg) => write!(f, "signing error : {}", msg),
+            SuiError::DryRun(msg) => write!(f, "dry run failed, transaction not signed : {}", msg),
+            SuiError::WebSocket(msg) => write!(f, "websocket error : {}", msg),
+            SuiError::ChainMismatch { expected, actual } => write!(
+                f,
+                "chain identifier mismatch, expected {} but gateway serves {}",
+                expected, actual
+            ),
+            SuiError::NotFound(msg) => write!(f, "not found : {}", msg),
+            SuiError::Storage(msg) => write!(f, "storage error : {}", msg),
+            SuiError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for SuiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SuiError::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SuiError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            SuiError::Decode(err.to_string())
+        } else {
+            SuiError::Transport(err)
+        }
+    }
+}
+
+impl From<serde_json::Error> for SuiError {
+    fn from(err: serde_json::Error) -> Self {
+        SuiError::Decode(err.to_string())
+    }
+}
+
+impl From<RpcError> for SuiError {
+    fn from(err: RpcError) -> Self {
+        SuiError::Rpc {
+            code: err.code,
+            message: err.message,
+            data: err.data,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for SuiError {
+    fn from(err: rusqlite::Error) -> Self {
+        SuiError::Storage(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for SuiError {
+    fn from(err: std::io::Error) -> Self {
+        SuiError::Storage(err.to_string())
+    }
+}
+
+impl From<Box<dyn Error>> for SuiError {
+    fn from(err: Box<dyn Error>) -> Self {
+        SuiError::Other(err.to_string())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::network::Network;
+use crate::utils::CustomErr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{env, fmt::Display, str::FromStr};
+
+const EXPLORER_ENV_NAME: &str = "explorer";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkKind {
+    Transaction,
+    Object,
+    Address,
+    Package,
+    Checkpoint,
+}
+
+impl LinkKind {
+    // 自定义模板中 {kind} 的取值
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinkKind::Transaction => "tx",
+            LinkKind::Object => "object",
+            LinkKind::Address => "address",
+            LinkKind::Package => "package",
+            LinkKind::Checkpoint => "checkpoint",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Explorer {
+    Suiscan,
+    SuiVision,
+    // 模板支持 {network} {kind} {id}，例如 https://explorer.example.com/{network}/{kind}/{id}
+    Custom(String),
+}
+
+// env explorer 可以是 suiscan 、suivision 或者自定义模板，默认使用 suiscan
+pub fn from_env() -> Explorer {
+    match env::var(EXPLORER_ENV_NAME) {
+        Ok(value) => value.parse().unwrap_or(Explorer::Suiscan),
+        Err(_) => Explorer::Suiscan,
+    }
+}
+
+// 优先使用 env explorer_{network}，例如 explorer_testnet，其次使用 env explorer
+pub fn for_network(network: &Network) -> Explorer {
+    match env::var(format!("{}_{}", EXPLORER_ENV_NAME, network_name(network))) {
+        Ok(value) => value.parse().unwrap_or_else(|_| from_env()),
+        Err(_) => from_env(),
+    }
+}
+
+// explorer 中使用的网络名字
+fn network_name(network: &Network) -> String {
+    match network {
+        Network::Localnet => String::from("local"),
+        Network::Custom(_) => String::from("custom"),
+        _ => network.to_string(),
+    }
+}
+
+// suiscan 和 suivision 只能展示公开网络的数据
+fn public_network_name(network: &Network) -> Option<String> {
+    match network {
+        Network::Mainnet | Network::Testnet | Network::Devnet => Some(network.to_string()),
+        _ => None,
+    }
+}
+
+impl Explorer {
+    // localnet 和自定义 rpc 的网络只有 Custom 模板可以生成链接，其他 explorer 返回 None
+    pub fn link(&self, network: &Network, kind: LinkKind, id: &str) -> Option<String> {
+        match self {
+            Explorer::Suiscan => {
+                let name = public_network_name(network)?;
+                let path = match kind {
+                    LinkKind::Transaction => "tx",
+                    LinkKind::Object | LinkKind::Package => "object",
+                    LinkKind::Address => "account",
+                    LinkKind::Checkpoint => "checkpoint",
+                };
+                Some(format!("https://suiscan.xyz/{}/{}/{}", name, path, id))
+            }
+            Explorer::SuiVision => {
+                let host = match network {
+                    Network::Mainnet => String::from("suivision.xyz"),
+                    _ => format!("{}.suivision.xyz", public_network_name(network)?),
+                };
+                let path = match kind {
+                    LinkKind::Transaction => "txblock",
+                    LinkKind::Object => "object",
+                    LinkKind::Address => "account",
+                    LinkKind::Package => "package",
+                    LinkKind::Checkpoint => "checkpoint",
+                };
+                Some(format!("https://{}/{}/{}", host, path, id))
+            }
+            Explorer::Custom(template) => Some(
+                template
+                    .replace("{network}", &network_name(network))
+                    .replace("{kind}", kind.name())
+                    .replace("{id}", id),
+            ),
+        }
+    }
+
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    pub fn to_string(&self) -> String {
+        match self {
+            Explorer::Suiscan => String::from("suiscan"),
+            Explorer::SuiVision => String::from("suivision"),
+            Explorer::Custom(template) => template.clone(),
+        }
+    }
+}
+
+impl FromStr for Explorer {
+    type Err = CustomErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "suiscan" => Ok(Explorer::Suiscan),
+            "suivision" => Ok(Explorer::SuiVision),
+            _ if value.contains("{id}") => Ok(Explorer::Custom(value.to_string())),
+            _ => Err(CustomErr::new(&format!("unknown explorer : {}", value))),
+        }
+    }
+}
+
+impl Serialize for Explorer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Explorer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Display for Explorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explorer_links() {
+        assert_eq!(
+            Explorer::Suiscan
+                .link(&Network::Testnet, LinkKind::Transaction, "abc")
+                .unwrap(),
+            "https://suiscan.xyz/testnet/tx/abc"
+        );
+        assert_eq!(
+            Explorer::SuiVision
+                .link(&Network::Mainnet, LinkKind::Package, "0x2")
+                .unwrap(),
+            "https://suivision.xyz/package/0x2"
+        );
+        assert_eq!(
+            Explorer::SuiVision
+                .link(&Network::Testnet, LinkKind::Address, "0x1")
+                .unwrap(),
+            "https://testnet.suivision.xyz/account/0x1"
+        );
+        // 本地网络和自定义 rpc 不会生成不存在的页面
+        let custom_rpc = Network::Custom("https://rpc.example.com".to_string());
+        assert!(Explorer::Suiscan
+            .link(&Network::Localnet, LinkKind::Transaction, "abc")
+            .is_none());
+        assert!(Explorer::SuiVision
+            .link(&custom_rpc, LinkKind::Object, "0x2")
+            .is_none());
+        let custom: Explorer = "https://scan.example.com/{network}/{kind}/{id}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            custom
+                .link(&Network::Localnet, LinkKind::Checkpoint, "42")
+                .unwrap(),
+            "https://scan.example.com/local/checkpoint/42"
+        );
+        assert!("suiexplorer".parse::<Explorer>().is_err());
+    }
+}
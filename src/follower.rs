@@ -0,0 +1,488 @@
+use crate::client::Client;
+use crate::error::SuiError;
+use crate::keystore::write_private_file;
+use crate::payload::EventFilter;
+use crate::response::{EventId, EventPage, SuiEvent};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::{fs, future::Future, path::Path, time::Duration};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+
+// 保存最后一个处理完成的 event id
+pub trait CheckpointStore {
+    fn load(&mut self) -> Result<Option<EventId>, SuiError>;
+    fn save(&mut self, cursor: &EventId) -> Result<(), SuiError>;
+}
+
+// cursor 以 json 格式保存在文件中
+pub struct FileCheckpoint {
+    path: String,
+}
+
+impl FileCheckpoint {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl CheckpointStore for FileCheckpoint {
+    fn load(&mut self) -> Result<Option<EventId>, SuiError> {
+        if !Path::new(&self.path).exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(
+            &self.path,
+        )?)?))
+    }
+
+    fn save(&mut self, cursor: &EventId) -> Result<(), SuiError> {
+        write_private_file(&self.path, &serde_json::to_string(cursor)?)?;
+        Ok(())
+    }
+}
+
+// 同一个数据库可以通过 name 保存多个 follower 的 cursor
+pub struct SqliteCheckpoint {
+    conn: Connection,
+    name: String,
+}
+
+impl SqliteCheckpoint {
+    pub fn open(path: &str, name: &str) -> Result<Self, SuiError> {
+        Self::from_connection(Connection::open(path)?, name)
+    }
+
+    pub fn from_connection(conn: Connection, name: &str) -> Result<Self, SuiError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_cursor (
+                name TEXT PRIMARY KEY,
+                tx_digest TEXT NOT NULL,
+                event_seq TEXT NOT NULL
+            )",
+            [],
+        )?;
+        Ok(Self {
+            conn,
+            name: name.to_string(),
+        })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    fn save_in(tx: &Connection, name: &str, cursor: &EventId) -> Result<(), SuiError> {
+        tx.execute(
+            "INSERT INTO event_cursor (name, tx_digest, event_seq) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET tx_digest = ?2, event_seq = ?3",
+            params![name, cursor.tx_digest, cursor.event_seq],
+        )?;
+        Ok(())
+    }
+
+    // f 中的写入和 cursor 在同一个事务中提交
+    pub fn commit_with<F>(&mut self, cursor: &EventId, f: F) -> Result<(), SuiError>
+    where
+        F: FnOnce(&Transaction) -> Result<(), SuiError>,
+    {
+        let tx = self.conn.transaction()?;
+        f(&tx)?;
+        Self::save_in(&tx, &self.name, cursor)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl CheckpointStore for SqliteCheckpoint {
+    fn load(&mut self) -> Result<Option<EventId>, SuiError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT tx_digest, event_seq FROM event_cursor WHERE name = ?1",
+                params![self.name],
+                |row| {
+                    Ok(EventId {
+                        tx_digest: row.get(0)?,
+                        event_seq: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn save(&mut self, cursor: &EventId) -> Result<(), SuiError> {
+        Self::save_in(&self.conn, &self.name, cursor)
+    }
+}
+
+// 区分查询 event 失败和 handler 或者 checkpoint 失败
+enum PollError {
+    Query(SuiError),
+    Handle(SuiError),
+}
+
+impl From<PollError> for SuiError {
+    fn from(err: PollError) -> Self {
+        match err {
+            PollError::Query(err) | PollError::Handle(err) => err,
+        }
+    }
+}
+
+// 轮询 suix_queryEvents，每处理完一个 event 就保存 cursor，重启后从 cursor 之后继续
+// handler 失败时 cursor 不前进，下次从失败的 event 重新开始
+pub struct EventFollower<'a, S: CheckpointStore> {
+    client: &'a Client,
+    filter: EventFilter,
+    store: S,
+    cursor: Option<EventId>,
+    page_size: u64,
+    poll_interval: Duration,
+}
+
+impl<'a, S: CheckpointStore> EventFollower<'a, S> {
+    pub fn new(client: &'a Client, filter: EventFilter, mut store: S) -> Result<Self, SuiError> {
+        let cursor = store.load()?;
+        Ok(Self {
+            client,
+            filter,
+            store,
+            cursor,
+            page_size: DEFAULT_PAGE_SIZE,
+            poll_interval: Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+        })
+    }
+
+    pub fn set_page_size(&mut self, page_size: u64) {
+        self.page_size = page_size;
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval;
+    }
+
+    pub fn cursor(&self) -> Option<&EventId> {
+        self.cursor.as_ref()
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    async fn next_page(&self) -> Result<EventPage, SuiError> {
+        self.client
+            .query_events(
+                &self.filter,
+                self.cursor.clone(),
+                Some(self.page_size),
+                false,
+            )
+            .await
+    }
+
+    // 空页但是 has_next_page 时移动到 next_cursor，cursor 没有前进时返回 false，避免重复请求同一页
+    fn skip_empty_page(&mut self, next_cursor: Option<EventId>) -> Result<bool, SuiError> {
+        match next_cursor {
+            Some(next) if Some(&next) != self.cursor.as_ref() => {
+                self.store.save(&next)?;
+                self.cursor = Some(next);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // 处理当前所有的新 event，返回处理的数量
+    // 进程在 handler 完成和保存 cursor 之间退出时，这个 event 会在重启后再次处理
+    // 需要严格只处理一次时使用 SqliteCheckpoint 和 poll_once_in_transaction
+    pub async fn poll_once<F, Fut>(&mut self, handler: &mut F) -> Result<usize, SuiError>
+    where
+        F: FnMut(SuiEvent) -> Fut,
+        Fut: Future<Output = Result<(), SuiError>>,
+    {
+        Ok(self.poll(handler).await?)
+    }
+
+    async fn poll<F, Fut>(&mut self, handler: &mut F) -> Result<usize, PollError>
+    where
+        F: FnMut(SuiEvent) -> Fut,
+        Fut: Future<Output = Result<(), SuiError>>,
+    {
+        let mut count = 0;
+        loop {
+            let page = self.next_page().await.map_err(PollError::Query)?;
+            let empty = page.data.is_empty();
+            for event in page.data {
+                let id = event.id.clone();
+                handler(event).await.map_err(PollError::Handle)?;
+                self.store.save(&id).map_err(PollError::Handle)?;
+                self.cursor = Some(id);
+                count += 1;
+            }
+            if !page.has_next_page
+                || (empty
+                    && !self
+                        .skip_empty_page(page.next_cursor)
+                        .map_err(PollError::Handle)?)
+            {
+                return Ok(count);
+            }
+        }
+    }
+
+    // 一直轮询，查询 event 失败时等待下一轮，handler 或者 checkpoint 失败时返回
+    pub async fn run<F, Fut>(&mut self, mut handler: F) -> Result<(), SuiError>
+    where
+        F: FnMut(SuiEvent) -> Fut,
+        Fut: Future<Output = Result<(), SuiError>>,
+    {
+        loop {
+            if let Err(PollError::Handle(err)) = self.poll(&mut handler).await {
+                return Err(err);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+impl<'a> EventFollower<'a, SqliteCheckpoint> {
+    // handler 在事务中写入数据，和 cursor 一起提交，崩溃重启后不会重复也不会遗漏
+    pub async fn poll_once_in_transaction<F>(&mut self, mut handler: F) -> Result<usize, SuiError>
+    where
+        F: FnMut(&Transaction, &SuiEvent) -> Result<(), SuiError>,
+    {
+        let mut count = 0;
+        loop {
+            let page = self.next_page().await?;
+            let empty = page.data.is_empty();
+            for event in page.data {
+                self.store
+                    .commit_with(&event.id, |tx| handler(tx, &event))?;
+                self.cursor = Some(event.id);
+                count += 1;
+            }
+            if !page.has_next_page || (empty && !self.skip_empty_page(page.next_cursor)?) {
+                return Ok(count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{default_client, tests::mock_rpc};
+    use crate::network::Network;
+    use crate::utils::current_timestamp;
+    use serde_json::{json, Value};
+
+    // 共 5 个 event，按照 cursor 每页返回 2 个
+    fn events_handler(request: Value) -> Value {
+        let start = match request["params"][1]["eventSeq"].as_str() {
+            Some(seq) => seq.parse::<usize>().unwrap() + 1,
+            None => 0,
+        };
+        let end = (start + 2).min(5);
+        let data: Vec<Value> = (start..end)
+            .map(|seq| {
+                json!({
+                    "id": {"txDigest": "abc", "eventSeq": seq.to_string()},
+                    "type": "0x5::hello_world::Minted",
+                    "parsedJson": {"seq": seq}
+                })
+            })
+            .collect();
+        let next_cursor = match end {
+            0 => Value::Null,
+            _ => json!({"txDigest": "abc", "eventSeq": (end - 1).to_string()}),
+        };
+        json!({"jsonrpc": "2.0", "id": request["id"], "result": {
+            "data": data,
+            "nextCursor": next_cursor,
+            "hasNextPage": end < 5
+        }})
+    }
+
+    #[tokio::test]
+    async fn test_file_checkpoint_resume() {
+        let url = mock_rpc(events_handler).await;
+        let client = default_client(Network::Custom(url));
+        let path = std::env::temp_dir()
+            .join(format!("sui_follower_{}.json", current_timestamp()))
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        // 第 3 个 event 处理失败，cursor 停在第 2 个
+        let mut seen = vec![];
+        let mut follower = client
+            .event_follower(EventFilter::package("0x5"), FileCheckpoint::new(&path))
+            .unwrap();
+        let result = follower
+            .poll_once(&mut |event: SuiEvent| {
+                let seq = event.id.event_seq.clone();
+                let failed = seq == "2";
+                if !failed {
+                    seen.push(seq);
+                }
+                async move {
+                    match failed {
+                        true => Err(SuiError::other("handler failed")),
+                        false => Ok(()),
+                    }
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(seen, vec!["0", "1"]);
+
+        // 重启后从第 3 个 event 继续
+        let mut follower = client
+            .event_follower(EventFilter::package("0x5"), FileCheckpoint::new(&path))
+            .unwrap();
+        assert_eq!(follower.cursor().unwrap().event_seq, "1");
+        let count = follower
+            .poll_once(&mut |event: SuiEvent| {
+                seen.push(event.id.event_seq);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(seen, vec!["0", "1", "2", "3", "4"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    // 空页 + has_next_page 时跳到 next_cursor，next_cursor 不变时停止
+    #[tokio::test]
+    async fn test_empty_page_with_next_page() {
+        let url = mock_rpc(|request| {
+            let (data, next_cursor) = match request["params"][1]["eventSeq"].as_str() {
+                None => (json!([]), "1"),
+                Some("1") => (
+                    json!([{"id": {"txDigest": "abc", "eventSeq": "2"}, "type": "0x5::hello_world::Minted"}]),
+                    "2",
+                ),
+                Some(_) => (json!([]), "2"),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": {
+                "data": data,
+                "nextCursor": {"txDigest": "abc", "eventSeq": next_cursor},
+                "hasNextPage": true
+            }})
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        let store =
+            SqliteCheckpoint::from_connection(Connection::open_in_memory().unwrap(), "minted")
+                .unwrap();
+        let mut follower = client
+            .event_follower(EventFilter::package("0x5"), store)
+            .unwrap();
+        let mut seen = vec![];
+        let count = tokio::time::timeout(
+            Duration::from_secs(5),
+            follower.poll_once(&mut |event: SuiEvent| {
+                seen.push(event.id.event_seq);
+                async { Ok(()) }
+            }),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(seen, vec!["2"]);
+        assert_eq!(follower.cursor().unwrap().event_seq, "2");
+    }
+
+    // 第一次查询返回 rpc error ，run 在下一轮继续，handler 失败时返回
+    #[tokio::test]
+    async fn test_run_retry_query_error() {
+        let requests = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = requests.clone();
+        let url = mock_rpc(move |request| {
+            if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return json!({"jsonrpc": "2.0", "id": request["id"],
+                    "error": {"code": -32603, "message": "internal error"}});
+            }
+            events_handler(request)
+        })
+        .await;
+        let client = default_client(Network::Custom(url));
+        let store =
+            SqliteCheckpoint::from_connection(Connection::open_in_memory().unwrap(), "minted")
+                .unwrap();
+        let mut follower = client
+            .event_follower(EventFilter::package("0x5"), store)
+            .unwrap();
+        follower.set_poll_interval(Duration::from_millis(10));
+        let mut seen = vec![];
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            follower.run(|event: SuiEvent| {
+                seen.push(event.id.event_seq.clone());
+                async move {
+                    match event.id.event_seq.as_str() {
+                        "4" => Err(SuiError::other("handler failed")),
+                        _ => Ok(()),
+                    }
+                }
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, Err(SuiError::Other(_))));
+        assert_eq!(seen, vec!["0", "1", "2", "3", "4"]);
+        assert_eq!(follower.cursor().unwrap().event_seq, "3");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_checkpoint_in_transaction() {
+        let url = mock_rpc(events_handler).await;
+        let client = default_client(Network::Custom(url));
+        let store =
+            SqliteCheckpoint::from_connection(Connection::open_in_memory().unwrap(), "minted")
+                .unwrap();
+        store
+            .connection()
+            .execute("CREATE TABLE minted (seq TEXT PRIMARY KEY)", [])
+            .unwrap();
+        let mut follower = client
+            .event_follower(EventFilter::package("0x5"), store)
+            .unwrap();
+        let count = follower
+            .poll_once_in_transaction(|tx, event| {
+                tx.execute(
+                    "INSERT INTO minted (seq) VALUES (?1)",
+                    params![event.id.event_seq],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 5);
+        let conn = follower.store().connection();
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM minted", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 5);
+        assert_eq!(follower.cursor().unwrap().event_seq, "4");
+
+        // handler 失败时事务回滚，cursor 不前进
+        let mut store = follower.store;
+        assert_eq!(store.load().unwrap().unwrap().event_seq, "4");
+        let cursor = EventId {
+            tx_digest: String::from("abc"),
+            event_seq: String::from("5"),
+        };
+        let result = store.commit_with(&cursor, |tx| {
+            tx.execute("INSERT INTO minted (seq) VALUES ('5')", [])?;
+            Err(SuiError::other("handler failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(store.load().unwrap().unwrap().event_seq, "4");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::error::SuiError;
+use crate::response::GasUsed;
+use crate::utils::{CustomErr, ADVISE_GAS_BUDGET};
+use std::{env, fmt::Display, str::FromStr};
+
+const GAS_BUDGET_ENV_NAME: &str = "gas_budget";
+const DEFAULT_MARGIN: f64 = 1.2;
+// 和 sui sdk 一致，computation 额外加上 1000 * reference gas price
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+// 构建交易时 gas budget 的取值方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GasBudget {
+    Fixed(u64),
+    // dry run 测得的 gas 乘以 margin
+    Estimated { margin: f64 },
+    // 估算值超过 cap 时使用 cap
+    Capped { margin: f64, cap: u64 },
+}
+
+impl GasBudget {
+    pub fn estimated() -> Self {
+        GasBudget::Estimated {
+            margin: DEFAULT_MARGIN,
+        }
+    }
+
+    pub fn capped(cap: u64) -> Self {
+        GasBudget::Capped {
+            margin: DEFAULT_MARGIN,
+            cap,
+        }
+    }
+
+    // dry run 时使用的 budget，gas coin 的余额需要不小于这个值
+    pub fn probe(&self) -> u64 {
+        match self {
+            GasBudget::Fixed(budget) => *budget,
+            GasBudget::Estimated { .. } => ADVISE_GAS_BUDGET,
+            GasBudget::Capped { cap, .. } => *cap,
+        }
+    }
+}
+
+impl Default for GasBudget {
+    fn default() -> Self {
+        Self::estimated()
+    }
+}
+
+impl Display for GasBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GasBudget::Fixed(budget) => write!(f, "{}", budget),
+            GasBudget::Estimated { margin } => write!(f, "estimate x{}", margin),
+            GasBudget::Capped { margin, cap } => write!(f, "estimate x{} , cap {}", margin, cap),
+        }
+    }
+}
+
+// 数字为固定的 budget，estimate 为估算，estimate:<cap> 为带上限的估算
+impl FromStr for GasBudget {
+    type Err = CustomErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let invalid = || CustomErr::new(&format!("invalid gas budget : {}", value));
+        match value.split_once(':') {
+            Some(("estimate", cap)) => Ok(Self::capped(cap.parse().map_err(|_| invalid())?)),
+            None if value == "estimate" => Ok(Self::estimated()),
+            None => Ok(GasBudget::Fixed(value.parse().map_err(|_| invalid())?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// env gas_budget 未设置或者无法解析时使用估算
+pub fn from_env() -> GasBudget {
+    match env::var(GAS_BUDGET_ENV_NAME) {
+        Ok(value) => value.parse().unwrap_or_default(),
+        Err(_) => GasBudget::default(),
+    }
+}
+
+// computation 加上 1000 * rgp 后计算 (computation + storage - rebate) * margin，不低于 computation * margin
+pub fn estimate(gas_used: &GasUsed, rgp: u64, margin: f64) -> Result<u64, SuiError> {
+    let parse = |value: &str| {
+        value
+            .parse::<u64>()
+            .map_err(|_| SuiError::Decode(format!("invalid gas cost {}", value)))
+    };
+    let computation = parse(&gas_used.computation_cost)? + GAS_SAFE_OVERHEAD * rgp;
+    let net = (computation + parse(&gas_used.storage_cost)?)
+        .saturating_sub(parse(&gas_used.storage_rebate)?)
+        .max(computation);
+    Ok((net as f64 * margin).ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_and_parse() {
+        let gas_used = |computation: &str, storage: &str, rebate: &str| GasUsed {
+            computation_cost: computation.to_string(),
+            storage_cost: storage.to_string(),
+            storage_rebate: rebate.to_string(),
+            non_refundable_storage_fee: String::from("0"),
+        };
+        assert_eq!(
+            estimate(&gas_used("1000", "2000", "500"), 1, 1.2).unwrap(),
+            4200
+        );
+        // rebate 大于 storage 时不低于 computation
+        assert_eq!(
+            estimate(&gas_used("1000", "0", "5000"), 1, 1.5).unwrap(),
+            3000
+        );
+        assert!(estimate(&gas_used("x", "0", "0"), 1, 1.0).is_err());
+
+        assert_eq!(
+            "5000000".parse::<GasBudget>().unwrap(),
+            GasBudget::Fixed(5000000)
+        );
+        assert_eq!(
+            "estimate".parse::<GasBudget>().unwrap(),
+            GasBudget::estimated()
+        );
+        assert_eq!(
+            "estimate:100".parse::<GasBudget>().unwrap(),
+            GasBudget::capped(100)
+        );
+        assert!("estimate:x".parse::<GasBudget>().is_err());
+        assert!("fixed:100".parse::<GasBudget>().is_err());
+    }
+}
@@ -1,13 +1,17 @@
 use crate::{
     account::SuiAccount,
     client::Client,
+    error::SuiError,
+    gas::GasBudget,
     network::Network,
     print_beauty,
+    response::TransactionEffectResult,
     utils::{self, current_timestamp},
 };
 use serde_json::Value;
 
 const GAS_EXPIRED_MS: u64 = 300_000;
+#[derive(Default)]
 struct UpdateGas {
     gas_object: String,
     expire_at: u64,
@@ -20,20 +24,12 @@ impl UpdateGas {
     }
 }
 
-impl Default for UpdateGas {
-    fn default() -> Self {
-        Self {
-            gas_object: String::from(""),
-            expire_at: 0,
-        }
-    }
-}
-
 pub struct HookCaller {
     target: Target,
     account: SuiAccount,
     client: Client,
     gas: UpdateGas,
+    gas_budget: GasBudget,
 }
 
 pub struct Target {
@@ -62,58 +58,54 @@ impl HookCaller {
     pub fn get_network(&self) -> &Network {
         &self.client.network
     }
-    pub async fn call(&mut self, type_arguments: Vec<String>, arguments: Vec<Value>) {
-        self.update_gas().await;
+    pub async fn call(
+        &mut self,
+        type_arguments: Vec<String>,
+        arguments: Vec<Value>,
+    ) -> Result<TransactionEffectResult, SuiError> {
+        self.update_gas().await?;
         print_beauty!("you will call sui network : ");
 
-        let result = self
+        let mut unsafe_transaction = self
             .client
-            .unsafe_move_call(
-                self.account.to_address(),
-                self.target.package.to_string(),
-                self.target.module.to_string(),
-                self.target.fun_name.to_string(),
-                type_arguments,
-                arguments,
-                self.gas.gas_object.to_string(),
-                utils::ADVISE_GAS_BUDGET,
-            )
-            .await
-            .unwrap();
+            .build_with_budget(self.gas_budget, |budget| {
+                self.client.unsafe_move_call(
+                    self.account.to_address(),
+                    self.target.package.to_string(),
+                    self.target.module.to_string(),
+                    self.target.fun_name.to_string(),
+                    type_arguments.clone(),
+                    arguments.clone(),
+                    self.gas.gas_object.to_string(),
+                    budget,
+                )
+            })
+            .await?;
+        // move abort 等错误在 dry run 时发现，不消耗 gas。估算 budget 时已经 dry run 过
+        if let GasBudget::Fixed(_) = self.gas_budget {
+            unsafe_transaction.set_dry_run();
+        }
 
-        let effet = result
-            .result
+        let effet = unsafe_transaction
             .with_signed_execute(&self.client, &self.account)
-            .await
-            .unwrap();
-        print_beauty!(
-            "transaction goes : {}",
-            self.client.network.transaction_link(&effet.result.digest)
-        );
+            .await?;
+        if let Some(link) = self.client.network.transaction_link(&effet.digest) {
+            print_beauty!("transaction goes : {}", link);
+        }
+        Ok(effet)
     }
 
-    async fn update_gas(&mut self) {
+    async fn update_gas(&mut self) -> Result<(), SuiError> {
         if self.gas.expired() {
             print_beauty!("now update gas!!!!");
-            match self
+            let gas_result = self
                 .client
-                .get_avaliable_gas(self.account.to_address(), utils::ADVISE_GAS_BUDGET)
-                .await
-            {
-                Err(err) => {
-                    print_beauty!(
-                        "gas error {} , with address: {}",
-                        err,
-                        self.account.to_address()
-                    );
-                    panic!();
-                }
-                Ok(gas_result) => {
-                    self.gas.gas_object = gas_result.coin_object_id;
-                    self.gas.expire_at = current_timestamp() + GAS_EXPIRED_MS;
-                }
-            }
+                .get_avaliable_gas(self.account.to_address(), self.gas_budget.probe())
+                .await?;
+            self.gas.gas_object = gas_result.coin_object_id;
+            self.gas.expire_at = current_timestamp() + GAS_EXPIRED_MS;
         }
+        Ok(())
     }
 
     pub fn new(target: Target, account: SuiAccount, client: Client) -> Self {
@@ -122,9 +114,16 @@ impl HookCaller {
             account,
             client,
             gas: UpdateGas::default(),
+            gas_budget: GasBudget::default(),
         }
     }
 
+    pub fn set_gas_budget(&mut self, gas_budget: GasBudget) {
+        self.gas_budget = gas_budget;
+        // 重新选择余额足够的 gas object
+        self.gas.expire_at = 0;
+    }
+
     pub fn get_account(&self) -> &SuiAccount {
         &self.account
     }
@@ -1,19 +1,24 @@
+use std::fmt::Display;
+
 pub struct HookData {
     account: String,
     balance: u64,
     network: crate::network::Network,
 }
 
-impl HookData {
-    pub fn to_string(&self) -> String {
-        String::from(format!(
+impl Display for HookData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
             "account: {}, balance: {},network:{}",
             self.account,
             self.balance,
             self.network.to_string()
-        ))
+        )
     }
+}
 
+impl HookData {
     pub fn new(account: &str) -> Self {
         Self {
             account: String::from(account),
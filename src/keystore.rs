@@ -1,33 +1,255 @@
-use crate::{account::SuiAccount, utils::CustomErr};
+use crate::{
+    account::{SignatureScheme, SuiAccount, SUI_PRIV_KEY_PREFIX},
+    config::ClientConfig,
+    utils::{base64_decode, base64_encode, CustomErr},
+    verify::normalize_address,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305, XNonce};
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::{env, error::Error, fs::File, io::Read};
+use std::{
+    env,
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::Path,
+};
+
+const ACCOUNT_ENV_NAME: &str = "sui_account";
+
+// (alias, address, scheme)
+pub type KeystoreEntry = (Option<String>, String, SignatureScheme);
 
 #[derive(Serialize, Deserialize)]
 pub struct Keystore {
     values: Vec<String>,
+    #[serde(skip)]
+    path: String,
+    #[serde(skip)]
+    password: Option<String>,
+    #[serde(skip)]
+    aliases: Vec<Alias>,
+}
+
+// sui.aliases 中的一项，public_key_base64 为 base64(flag || public_key)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Alias {
+    pub alias: String,
+    pub public_key_base64: String,
+}
+
+const ENCRYPTED_KEYSTORE_VERSION: u32 = 1;
+const KDF_ARGON2ID: &str = "argon2id";
+const CIPHER_XCHACHA20POLY1305: &str = "xchacha20poly1305";
+// 加载时 kdf 参数的上限，避免损坏或者伪造的文件占用过多内存和 cpu ，m_cost 单位为 KiB
+const MAX_M_COST: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 16;
+const MAX_P_COST: u32 = 8;
+
+// 加密 keystore 的文件格式，ciphertext 解密后为 sui.keystore 相同的 json 数组
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u32,
+    kdf: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<[u8; 32], Box<dyn Error>> {
+    let params = Params::new(m_cost, t_cost, p_cost, Some(32))
+        .map_err(|err| CustomErr::new_box(&format!("invalid kdf params : {}", err)))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|err| CustomErr::new_box(&format!("derive key failed : {}", err)))?;
+    Ok(key)
+}
+
+// 先写入临时文件再 rename，避免写入中断导致 keystore 损坏，文件权限保持 0600
+pub(crate) fn write_private_file(path: &str, contents: &str) -> Result<(), Box<dyn Error>> {
+    let tmp_path = format!("{}.tmp", path);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// 优先从 passphrase 文件读取密码，否则在终端提示输入
+pub fn read_password(passphrase_file: Option<&str>) -> Result<String, Box<dyn Error>> {
+    match passphrase_file {
+        Some(path) => Ok(fs::read_to_string(path)?
+            .trim_end_matches(['\r', '\n'])
+            .to_string()),
+        None => Ok(rpassword::prompt_password("keystore password: ")?),
+    }
 }
 
+// 优先使用 client.yaml 中配置的 keystore 路径
 pub fn default_path() -> String {
+    if let Some(path) = ClientConfig::load_default().and_then(|config| config.keystore_path()) {
+        return path;
+    }
     format!("{}/.sui/sui_config/sui.keystore", env::var("HOME").unwrap())
 }
 
-impl Keystore {
-    pub fn default() -> Self {
+fn actual_path(path: &str) -> String {
+    if path.is_empty() {
+        default_path()
+    } else {
+        path.to_owned()
+    }
+}
+
+// account-<n> 格式，n 从 start 开始递增到未被使用
+fn unique_alias(aliases: &[Alias], start: usize) -> String {
+    (start..)
+        .map(|n| format!("account-{}", n))
+        .find(|alias| !aliases.iter().any(|item| &item.alias == alias))
+        .unwrap()
+}
+
+// sui.aliases 和 keystore 在同一个目录，例如 sui.keystore 对应 sui.aliases
+pub fn aliases_path(keystore_path: &str) -> String {
+    Path::new(keystore_path)
+        .with_extension("aliases")
+        .to_string_lossy()
+        .to_string()
+}
+
+fn load_aliases(keystore_path: &str) -> Result<Vec<Alias>, Box<dyn Error>> {
+    let path = aliases_path(keystore_path);
+    if !Path::new(&path).exists() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+impl Default for Keystore {
+    fn default() -> Self {
         Self::load_from("")
     }
+}
+
+impl Keystore {
+    // 创建一个空的 keystore，save 的时候写入 path
+    pub fn new(path: &str) -> Self {
+        Self {
+            values: vec![],
+            path: actual_path(path),
+            password: None,
+            aliases: vec![],
+        }
+    }
+
     pub fn load_from(path: &str) -> Self {
-        let actual_path = if path.is_empty() {
-            default_path()
-        } else {
-            path.to_owned()
-        };
-        let mut file = File::open(actual_path).expect("can't open keystore file");
+        let actual_path = actual_path(path);
+        let mut file = File::open(&actual_path).expect("can't open keystore file");
         let mut contents = String::new();
         file.read_to_string(&mut contents)
             .expect("read file failed...");
 
         let values = serde_json::from_str(&contents).expect("无法反序列化数据");
-        Self { values }
+        let aliases = load_aliases(&actual_path).expect("can't load aliases file");
+        Self {
+            values,
+            path: actual_path,
+            password: None,
+            aliases,
+        }
+    }
+
+    // 加载加密的 keystore，之后 save 会使用相同的密码重新加密
+    pub fn load_encrypted(path: &str, password: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let encrypted: EncryptedKeystoreFile = serde_json::from_str(&contents)?;
+        if encrypted.version != ENCRYPTED_KEYSTORE_VERSION
+            || encrypted.kdf != KDF_ARGON2ID
+            || encrypted.cipher != CIPHER_XCHACHA20POLY1305
+        {
+            return Err(CustomErr::new_box("unsupported encrypted keystore format"));
+        }
+        if encrypted.m_cost > MAX_M_COST
+            || encrypted.t_cost > MAX_T_COST
+            || encrypted.p_cost > MAX_P_COST
+        {
+            return Err(CustomErr::new_box(&format!(
+                "kdf params too large : m_cost {} , t_cost {} , p_cost {}",
+                encrypted.m_cost, encrypted.t_cost, encrypted.p_cost
+            )));
+        }
+        let key = derive_key(
+            password,
+            &base64_decode(&encrypted.salt)?,
+            encrypted.m_cost,
+            encrypted.t_cost,
+            encrypted.p_cost,
+        )?;
+        let nonce: [u8; 24] = base64_decode(&encrypted.nonce)?
+            .try_into()
+            .map_err(|_| CustomErr::new_box("invalid encrypted keystore nonce"))?;
+        let plaintext = XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                &XNonce::from(nonce),
+                base64_decode(&encrypted.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| CustomErr::new_box("wrong password or corrupted keystore"))?;
+        Ok(Self {
+            values: serde_json::from_slice(&plaintext)?,
+            path: path.to_owned(),
+            password: Some(password.to_owned()),
+            aliases: load_aliases(path)?,
+        })
+    }
+
+    // 把明文的 sui.keystore 加密保存到 encrypted_path，原文件保持不变
+    pub fn migrate_to_encrypted(
+        plain_path: &str,
+        encrypted_path: &str,
+        password: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut store = Self::load_from(plain_path);
+        store.path = encrypted_path.to_owned();
+        store.password = Some(password.to_owned());
+        store.save()?;
+        Ok(store)
+    }
+
+    // 文件不存在时返回空的 keystore
+    pub fn load_or_create(path: &str) -> Self {
+        if Path::new(&actual_path(path)).exists() {
+            Self::load_from(path)
+        } else {
+            Self::new(path)
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
     }
 
     pub fn load_account(&self, idx: usize) -> Result<SuiAccount, Box<dyn Error>> {
@@ -39,9 +261,247 @@ impl Keystore {
         }
     }
 
+    pub fn find_by_address(&self, address: &str) -> Result<SuiAccount, Box<dyn Error>> {
+        match self.position(address) {
+            Some(idx) => SuiAccount::from_keystore(&self.values[idx]),
+            None => Err(CustomErr::new_box(&format!(
+                "account {} not found in keystore",
+                address
+            ))),
+        }
+    }
+
+    pub fn find_by_alias(&self, alias: &str) -> Result<SuiAccount, Box<dyn Error>> {
+        let public_key = match self.aliases.iter().find(|item| item.alias == alias) {
+            Some(item) => &item.public_key_base64,
+            None => return Err(CustomErr::new_box(&format!("alias {} not found", alias))),
+        };
+        for value in &self.values {
+            let account = SuiAccount::from_keystore(value)?;
+            if &account.public_key_base64() == public_key {
+                return Ok(account);
+            }
+        }
+        Err(CustomErr::new_box(&format!(
+            "account of alias {} not found in keystore",
+            alias
+        )))
+    }
+
+    // 0x 开头时按地址查找，否则按 alias 查找
+    pub fn select_account(&self, key: &str) -> Result<SuiAccount, Box<dyn Error>> {
+        if key.starts_with("0x") {
+            self.find_by_address(key)
+        } else {
+            self.find_by_alias(key)
+        }
+    }
+
+    // env sui_account 指定地址或者 alias，未设置时使用 client.yaml 中的 active_address，都没有时使用第一个账号
+    pub fn account_from_env(&self) -> Result<SuiAccount, Box<dyn Error>> {
+        match env::var(ACCOUNT_ENV_NAME) {
+            Ok(key) if !key.is_empty() => self.select_account(&key),
+            _ => self.default_account(ClientConfig::load_default().as_ref()),
+        }
+    }
+
+    // 只有 client.yaml 中配置的 keystore 才使用 active_address，地址不在当前 keystore 中时使用第一个账号
+    fn default_account(&self, config: Option<&ClientConfig>) -> Result<SuiAccount, Box<dyn Error>> {
+        let active = config
+            .filter(|config| config.keystore_path().as_deref() == Some(self.path.as_str()))
+            .and_then(|config| config.active_address.as_ref())
+            .and_then(|address| self.find_by_address(address).ok());
+        match active {
+            Some(account) => Ok(account),
+            None => self.load_account(0),
+        }
+    }
+
+    pub fn alias_of(&self, account: &SuiAccount) -> Option<String> {
+        let public_key = account.public_key_base64();
+        self.aliases
+            .iter()
+            .find(|item| item.public_key_base64 == public_key)
+            .map(|item| item.alias.to_string())
+    }
+
+    // 返回 (alias, address, scheme) 列表，顺序与 keystore 一致
+    pub fn list(&self) -> Result<Vec<KeystoreEntry>, Box<dyn Error>> {
+        let mut items = vec![];
+        for value in &self.values {
+            let account = SuiAccount::from_keystore(value)?;
+            items.push((
+                self.alias_of(&account),
+                account.to_address(),
+                account.scheme(),
+            ));
+        }
+        Ok(items)
+    }
+
+    fn position(&self, address: &str) -> Option<usize> {
+        let address = normalize_address(address);
+        self.values.iter().position(|value| {
+            SuiAccount::from_keystore(value)
+                .map(|account| account.to_address() == address)
+                .unwrap_or(false)
+        })
+    }
+
+    fn push_account(&mut self, account: &SuiAccount) -> Result<(), Box<dyn Error>> {
+        if self.position(&account.to_address()).is_some() {
+            return Err(CustomErr::new_box(&format!(
+                "account {} already exists in keystore",
+                account.to_address()
+            )));
+        }
+        self.values.push(account.to_keystore());
+        Ok(())
+    }
+
+    // 使用 sui.aliases 时每个 key 都需要 alias，否则 sui cli 按地址查找 alias 会失败
+    fn uses_aliases(&self) -> bool {
+        !self.aliases.is_empty() || Path::new(&aliases_path(&actual_path(&self.path))).exists()
+    }
+
+    // 使用 sui.aliases 时自动生成 alias
+    pub fn add_account(&mut self, account: &SuiAccount) -> Result<(), Box<dyn Error>> {
+        if self.uses_aliases() {
+            let alias = unique_alias(&self.aliases, self.values.len());
+            return self.add_account_with_alias(account, &alias);
+        }
+        self.push_account(account)
+    }
+
+    pub fn add_account_with_alias(
+        &mut self,
+        account: &SuiAccount,
+        alias: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.aliases.iter().any(|item| item.alias == alias) {
+            return Err(CustomErr::new_box(&format!(
+                "alias {} already exists",
+                alias
+            )));
+        }
+        self.push_account(account)?;
+        self.aliases.push(Alias {
+            alias: alias.to_string(),
+            public_key_base64: account.public_key_base64(),
+        });
+        Ok(())
+    }
+
+    pub fn remove_by_address(&mut self, address: &str) -> Result<SuiAccount, Box<dyn Error>> {
+        match self.position(address) {
+            Some(idx) => {
+                let account = SuiAccount::from_keystore(&self.values.remove(idx))?;
+                let public_key = account.public_key_base64();
+                self.aliases
+                    .retain(|item| item.public_key_base64 != public_key);
+                Ok(account)
+            }
+            None => Err(CustomErr::new_box(&format!(
+                "account {} not found in keystore",
+                address
+            ))),
+        }
+    }
+
+    // 导入 suiprivkey 开头的 bech32 私钥，或者 sui.keystore 中的 base64 格式，返回导入的地址
+    pub fn import(&mut self, key: &str) -> Result<String, Box<dyn Error>> {
+        let key = key.trim();
+        let account = if key.starts_with(SUI_PRIV_KEY_PREFIX) {
+            SuiAccount::from_bech32(key)?
+        } else {
+            SuiAccount::from_keystore(key)?
+        };
+        self.add_account(&account)?;
+        Ok(account.to_address())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.password.is_some()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        match &self.password {
+            Some(password) => self.save_encrypted(&self.path, password),
+            None => self.save_to(&self.path),
+        }
+    }
+
+    pub fn save_to(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let contents = serde_json::to_string_pretty(&self.values)?;
+        write_private_file(&actual_path(path), &contents)?;
+        self.save_aliases(path)
+    }
+
+    fn save_aliases(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let path = aliases_path(&actual_path(path));
+        if self.aliases.is_empty() && !Path::new(&path).exists() {
+            return Ok(());
+        }
+        // 之前没有 alias 的 key 补上生成的 alias
+        let mut aliases = self.aliases.clone();
+        for (idx, value) in self.values.iter().enumerate() {
+            let public_key = SuiAccount::from_keystore(value)?.public_key_base64();
+            if !aliases
+                .iter()
+                .any(|item| item.public_key_base64 == public_key)
+            {
+                aliases.push(Alias {
+                    alias: unique_alias(&aliases, idx),
+                    public_key_base64: public_key,
+                });
+            }
+        }
+        write_private_file(&path, &serde_json::to_string_pretty(&aliases)?)
+    }
+
+    pub fn save_encrypted(&self, path: &str, password: &str) -> Result<(), Box<dyn Error>> {
+        let params = Params::default();
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        let mut csprng = OsRng {};
+        csprng.fill_bytes(&mut salt);
+        csprng.fill_bytes(&mut nonce);
+        let key = derive_key(
+            password,
+            &salt,
+            params.m_cost(),
+            params.t_cost(),
+            params.p_cost(),
+        )?;
+        let plaintext = serde_json::to_vec(&self.values)?;
+        let ciphertext = XChaCha20Poly1305::new(&key.into())
+            .encrypt(&XNonce::from(nonce), plaintext.as_slice())
+            .map_err(|_| CustomErr::new_box("encrypt keystore failed"))?;
+        let encrypted = EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: KDF_ARGON2ID.to_string(),
+            m_cost: params.m_cost(),
+            t_cost: params.t_cost(),
+            p_cost: params.p_cost(),
+            salt: base64_encode(&salt),
+            cipher: CIPHER_XCHACHA20POLY1305.to_string(),
+            nonce: base64_encode(&nonce),
+            ciphertext: base64_encode(&ciphertext),
+        };
+        write_private_file(
+            &actual_path(path),
+            &serde_json::to_string_pretty(&encrypted)?,
+        )?;
+        self.save_aliases(path)
+    }
+
     pub fn len(&self) -> usize {
         self.values.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
 }
 
 impl IntoIterator for Keystore {
@@ -52,3 +512,204 @@ impl IntoIterator for Keystore {
         (0..self.values.len()).collect::<Vec<usize>>().into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::SignatureScheme;
+
+    // 每个测试使用单独的目录，并行运行时不会写同一个文件
+    fn test_dir(name: &str) -> String {
+        let dir = env::temp_dir().join(format!(
+            "sui-rust-operator-{}-{}-{}",
+            name,
+            std::process::id(),
+            crate::utils::current_timestamp()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_add_save_and_remove() {
+        let dir = test_dir("save");
+        let path = format!("{}/sui.keystore", dir);
+        let path = path.as_str();
+
+        let mut store = Keystore::load_or_create(path);
+        assert!(store.is_empty());
+        let account = SuiAccount::generate(SignatureScheme::Secp256k1).unwrap();
+        store.add_account(&account).unwrap();
+        assert!(store.add_account(&account).is_err());
+        let imported = store
+            .import("AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF")
+            .unwrap();
+        store.save().unwrap();
+
+        let contents = fs::read_to_string(path).unwrap();
+        let values: Vec<String> = serde_json::from_str(&contents).unwrap();
+        assert_eq!(values[1], "AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let mut loaded = Keystore::load_from(path);
+        assert_eq!(loaded.len(), 2);
+        let removed = loaded.remove_by_address(&imported).unwrap();
+        assert_eq!(removed.to_address(), imported);
+        assert!(loaded.remove_by_address(&imported).is_err());
+        assert_eq!(
+            loaded.load_account(0).unwrap().to_address(),
+            account.to_address()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_encrypted_keystore() {
+        let dir = test_dir("encrypted");
+        let plain_path = format!("{}/sui.keystore", dir);
+        let encrypted_path = format!("{}/sui.keystore.encrypted", dir);
+
+        let mut plain = Keystore::new(&plain_path);
+        plain
+            .import("AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF")
+            .unwrap();
+        plain.save().unwrap();
+
+        let migrated =
+            Keystore::migrate_to_encrypted(&plain_path, &encrypted_path, "passw0rd").unwrap();
+        assert!(migrated.is_encrypted());
+        let contents = fs::read_to_string(&encrypted_path).unwrap();
+        assert!(!contents.contains("AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF"));
+        assert!(Keystore::load_encrypted(&encrypted_path, "wrong").is_err());
+
+        // kdf 参数超过上限时不派生秘钥
+        let crafted_path = format!("{}/sui.keystore.crafted", dir);
+        let mut crafted: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        crafted["m_cost"] = serde_json::Value::from(u32::MAX);
+        fs::write(&crafted_path, crafted.to_string()).unwrap();
+        match Keystore::load_encrypted(&crafted_path, "passw0rd") {
+            Err(err) => assert!(err.to_string().contains("kdf params too large")),
+            Ok(_) => panic!("expect kdf params error"),
+        }
+
+        let mut loaded = Keystore::load_encrypted(&encrypted_path, "passw0rd").unwrap();
+        assert_eq!(
+            loaded.load_account(0).unwrap().to_address(),
+            "0x0a27f6f7d3b7907fbcc4265ee8e63f5447312a8f53fb270a36f892e6f264008f"
+        );
+        loaded.add_account(&SuiAccount::new_account()).unwrap();
+        loaded.save().unwrap();
+        assert_eq!(
+            Keystore::load_encrypted(&encrypted_path, "passw0rd")
+                .unwrap()
+                .len(),
+            2
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_lookup_by_address_and_alias() {
+        let dir = test_dir("alias");
+        let path = format!("{}/sui.keystore", dir);
+
+        let mut store = Keystore::new(&path);
+        let first = SuiAccount::new_account();
+        let second = SuiAccount::generate(SignatureScheme::Secp256r1).unwrap();
+        store.add_account(&first).unwrap();
+        store
+            .add_account_with_alias(&second, "mobile-signer")
+            .unwrap();
+        store.save().unwrap();
+
+        let loaded = Keystore::load_from(&path);
+        assert_eq!(
+            loaded
+                .find_by_address(&second.to_address().to_uppercase().replace("0X", "0x"))
+                .unwrap()
+                .to_address(),
+            second.to_address()
+        );
+        assert_eq!(
+            loaded.select_account("mobile-signer").unwrap().to_address(),
+            second.to_address()
+        );
+        assert!(loaded.find_by_alias("unknown").is_err());
+        // 使用 sui.aliases 时没有指定 alias 的 key 也会写入生成的 alias
+        let items = loaded.list().unwrap();
+        assert_eq!(
+            items[0],
+            (
+                Some("account-0".to_string()),
+                first.to_address(),
+                SignatureScheme::ED25519
+            )
+        );
+        assert_eq!(
+            items[1],
+            (
+                Some("mobile-signer".to_string()),
+                second.to_address(),
+                SignatureScheme::Secp256r1
+            )
+        );
+
+        // aliases 文件已经存在时 add_account 和 import 生成不重复的 alias
+        let mut store = Keystore::load_from(&path);
+        store.add_account(&SuiAccount::new_account()).unwrap();
+        store
+            .import("AAI9gSWWADI9gC6E53o1pfhaPSdhxNbQGjT6zTIjeijF")
+            .unwrap();
+        store.save().unwrap();
+        let items = Keystore::load_from(&path).list().unwrap();
+        let aliases: Vec<String> = items.into_iter().map(|item| item.0.unwrap()).collect();
+        assert_eq!(
+            aliases,
+            vec!["account-0", "mobile-signer", "account-2", "account-3"]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_account_from_config() {
+        let mut store = Keystore::new("/tmp/sui-rust-operator/sui.keystore");
+        let first = SuiAccount::new_account();
+        let second = SuiAccount::new_account();
+        store.add_account(&first).unwrap();
+        store.add_account(&second).unwrap();
+        let config = |path: &str, address: &str| ClientConfig {
+            keystore: crate::config::KeystoreLocation::File(path.to_string()),
+            envs: vec![],
+            active_env: None,
+            active_address: Some(address.to_string()),
+        };
+
+        let active = config("/tmp/sui-rust-operator/sui.keystore", &second.to_address());
+        assert_eq!(
+            store.default_account(Some(&active)).unwrap().to_address(),
+            second.to_address()
+        );
+        // 其他 keystore 或者地址不在当前 keystore 中时使用第一个账号
+        let other = config("/tmp/other/sui.keystore", &second.to_address());
+        assert_eq!(
+            store.default_account(Some(&other)).unwrap().to_address(),
+            first.to_address()
+        );
+        let unknown = config("/tmp/sui-rust-operator/sui.keystore", "0x1");
+        assert_eq!(
+            store.default_account(Some(&unknown)).unwrap().to_address(),
+            first.to_address()
+        );
+        assert_eq!(
+            store.default_account(None).unwrap().to_address(),
+            first.to_address()
+        );
+    }
+}
@@ -1,10 +1,22 @@
 pub mod account;
 pub mod client;
+pub mod config;
+pub mod endpoint;
+pub mod error;
+pub mod explorer;
+pub mod follower;
+pub mod gas;
 pub mod hook;
 pub mod hookserver;
 pub mod keystore;
 pub mod r#macro;
+pub mod mnemonic;
+pub mod multisig;
 pub mod network;
+pub mod pagination;
 pub mod payload;
 pub mod response;
+pub mod retry;
+pub mod subscription;
 pub mod utils;
+pub mod verify;
@@ -0,0 +1,159 @@
+use crate::account::SignatureScheme;
+use crate::utils::CustomErr;
+use bip32::{DerivationPath, XPrv};
+use bip39::{Language, Mnemonic};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha512;
+use std::error::Error;
+
+const SUI_COIN_TYPE: u32 = 784;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+// 与 sui 钱包和 cli 相同的默认路径
+pub fn default_derivation_path(
+    scheme: SignatureScheme,
+    account: u32,
+) -> Result<String, Box<dyn Error>> {
+    match scheme {
+        SignatureScheme::ED25519 => Ok(format!("m/44'/{}'/{}'/0'/0'", SUI_COIN_TYPE, account)),
+        SignatureScheme::Secp256k1 => Ok(format!("m/54'/{}'/{}'/0/0", SUI_COIN_TYPE, account)),
+        SignatureScheme::Secp256r1 => Ok(format!("m/74'/{}'/{}'/0/0", SUI_COIN_TYPE, account)),
+        SignatureScheme::MultiSig => Err(CustomErr::new_box(
+            "multisig account can not be derived from mnemonic",
+        )),
+    }
+}
+
+// word_count 可以是 12, 15, 18, 21, 24
+pub fn generate_mnemonic(word_count: usize) -> Result<String, Box<dyn Error>> {
+    let entropy_len = match word_count {
+        12 | 15 | 18 | 21 | 24 => word_count / 3 * 4,
+        _ => {
+            return Err(CustomErr::new_box(
+                "mnemonic word count must be one of 12, 15, 18, 21, 24",
+            ))
+        }
+    };
+    let mut entropy = vec![0u8; entropy_len];
+    OsRng {}.fill_bytes(&mut entropy);
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)?;
+    Ok(mnemonic.to_string())
+}
+
+pub fn mnemonic_to_seed(phrase: &str) -> Result<[u8; 64], Box<dyn Error>> {
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase)?;
+    Ok(mnemonic.to_seed(""))
+}
+
+fn parse_path(path: &str) -> Result<Vec<u32>, Box<dyn Error>> {
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return Err(CustomErr::new_box("derivation path must start with m"));
+    }
+    let mut indexes = vec![];
+    for segment in segments {
+        let (number, hardened) = match segment.strip_suffix('\'') {
+            Some(number) => (number, true),
+            None => (segment, false),
+        };
+        let index: u32 = number.parse()?;
+        if index >= HARDENED_OFFSET {
+            return Err(CustomErr::new_box("derivation path index is too big"));
+        }
+        indexes.push(if hardened {
+            index + HARDENED_OFFSET
+        } else {
+            index
+        });
+    }
+    Ok(indexes)
+}
+
+// SLIP-0010 ed25519 只支持 hardened 路径
+fn derive_ed25519(seed: &[u8], path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let indexes = parse_path(path)?;
+    if indexes.iter().any(|index| index < &HARDENED_OFFSET) {
+        return Err(CustomErr::new_box(
+            "ed25519 derivation path must be fully hardened",
+        ));
+    }
+    let mut mac = Hmac::<Sha512>::new_from_slice(b"ed25519 seed")?;
+    mac.update(seed);
+    let mut node = mac.finalize().into_bytes().to_vec();
+    for index in indexes {
+        let mut mac = Hmac::<Sha512>::new_from_slice(&node[32..])?;
+        mac.update(&[0u8]);
+        mac.update(&node[..32]);
+        mac.update(&index.to_be_bytes());
+        node = mac.finalize().into_bytes().to_vec();
+    }
+    Ok(node[..32].to_vec())
+}
+
+// 返回路径对应的 secret_key bytes，secp256r1 和 sui 一样使用 BIP32 (secp256k1) 的派生结果
+pub fn derive_secret(
+    phrase: &str,
+    scheme: SignatureScheme,
+    path: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let seed = mnemonic_to_seed(phrase)?;
+    match scheme {
+        SignatureScheme::ED25519 => derive_ed25519(&seed, path),
+        SignatureScheme::Secp256k1 | SignatureScheme::Secp256r1 => {
+            let path: DerivationPath = path.parse()?;
+            let child = XPrv::derive_from_path(seed, &path)?;
+            Ok(child.private_key().to_bytes().to_vec())
+        }
+        SignatureScheme::MultiSig => Err(CustomErr::new_box(
+            "multisig account can not be derived from mnemonic",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::SuiAccount;
+
+    #[test]
+    fn test_mnemonic_to_seed() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(
+            hex::encode(mnemonic_to_seed(phrase).unwrap()),
+            "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"
+        );
+    }
+
+    #[test]
+    fn test_slip10_ed25519() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(derive_ed25519(&seed, "m").unwrap()),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(derive_ed25519(&seed, "m/0'").unwrap()),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert!(derive_ed25519(&seed, "m/0").is_err());
+    }
+
+    #[test]
+    fn test_generate_and_derive() {
+        for scheme in [
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::Secp256r1,
+        ] {
+            let (account, phrase) = SuiAccount::new_with_mnemonic(scheme, 24).unwrap();
+            assert_eq!(phrase.split(' ').count(), 24);
+            let restored = SuiAccount::from_mnemonic(&phrase, scheme, 0).unwrap();
+            assert_eq!(restored.to_address(), account.to_address());
+            let second = SuiAccount::from_mnemonic(&phrase, scheme, 1).unwrap();
+            assert_ne!(second.to_address(), account.to_address());
+        }
+        assert!(generate_mnemonic(13).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::account::{msg_hash, IntentScope, SignatureScheme, SuiAccount};
+use crate::payload::Payload;
+use crate::response::UnsafeTransactionResult;
+use crate::utils::{base64_encode, uleb128_decode, uleb128_encode, CustomErr};
+use std::{error::Error, fmt::Display};
+
+// sui 中 multisig 最多支持 10 个公钥
+pub const MAX_SIGNER_IN_MULTISIG: usize = 10;
+
+pub struct WeightedPublicKey {
+    pub scheme: SignatureScheme,
+    pub public_key: Vec<u8>,
+    pub weight: u8,
+}
+
+impl WeightedPublicKey {
+    pub fn new(scheme: SignatureScheme, public_key: Vec<u8>, weight: u8) -> Self {
+        Self {
+            scheme,
+            public_key,
+            weight,
+        }
+    }
+
+    pub fn from_account(account: &SuiAccount, weight: u8) -> Self {
+        Self::new(account.scheme(), account.public_key_bytes(), weight)
+    }
+}
+
+pub struct MultiSigAccount {
+    public_keys: Vec<WeightedPublicKey>,
+    threshold: u16,
+}
+
+impl MultiSigAccount {
+    pub fn new(
+        public_keys: Vec<WeightedPublicKey>,
+        threshold: u16,
+    ) -> Result<Self, Box<dyn Error>> {
+        if public_keys.is_empty() || public_keys.len() > MAX_SIGNER_IN_MULTISIG {
+            return Err(CustomErr::new_box(&format!(
+                "multisig needs 1 to {} public keys",
+                MAX_SIGNER_IN_MULTISIG
+            )));
+        }
+        if threshold == 0 {
+            return Err(CustomErr::new_box("multisig threshold must be positive"));
+        }
+        let mut total_weight: u16 = 0;
+        for (idx, key) in public_keys.iter().enumerate() {
+            if key.scheme == SignatureScheme::MultiSig {
+                return Err(CustomErr::new_box("multisig can not be nested"));
+            }
+            if key.weight == 0 {
+                return Err(CustomErr::new_box("public key weight must be positive"));
+            }
+            if public_keys[..idx]
+                .iter()
+                .any(|other| other.scheme == key.scheme && other.public_key == key.public_key)
+            {
+                return Err(CustomErr::new_box("duplicated public key in multisig"));
+            }
+            total_weight += key.weight as u16;
+        }
+        if total_weight < threshold {
+            return Err(CustomErr::new_box(
+                "multisig threshold is bigger than total weight",
+            ));
+        }
+        Ok(Self {
+            public_keys,
+            threshold,
+        })
+    }
+
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    pub fn public_keys(&self) -> &Vec<WeightedPublicKey> {
+        &self.public_keys
+    }
+
+    // address = blake2b(0x03 || threshold || flag_i || pk_i || weight_i ...)
+    pub fn to_address(&self) -> String {
+        let mut payload: Vec<u8> = vec![SignatureScheme::MultiSig.flag()];
+        payload.extend_from_slice(&self.threshold.to_le_bytes());
+        for key in &self.public_keys {
+            payload.push(key.scheme.flag());
+            payload.extend_from_slice(&key.public_key);
+            payload.push(key.weight);
+        }
+        let h = msg_hash(&payload);
+        format!("0x{}", h.to_hex())
+    }
+
+    fn position(&self, scheme: SignatureScheme, public_key: &[u8]) -> Option<usize> {
+        self.public_keys
+            .iter()
+            .position(|key| key.scheme == scheme && key.public_key == public_key)
+    }
+
+    // 每个 signature 的格式为 flag || sig || pubkey，即 SuiAccount::sign_data 的返回值
+    pub fn combine_signatures(&self, signatures: &[Vec<u8>]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut parts: Vec<(usize, u8, Vec<u8>)> = vec![];
+        let mut bitmap: u16 = 0;
+        let mut weight: u16 = 0;
+        for signature in signatures {
+            if signature.len() < 65 {
+                return Err(CustomErr::new_box("signature is too short"));
+            }
+            let scheme = SignatureScheme::from_flag(signature[0])?;
+            let idx = match self.position(scheme, &signature[65..]) {
+                Some(idx) => idx,
+                None => {
+                    return Err(CustomErr::new_box(
+                        "signature public key is not a member of multisig",
+                    ))
+                }
+            };
+            if bitmap & (1 << idx) != 0 {
+                return Err(CustomErr::new_box(
+                    "duplicated signature for one public key",
+                ));
+            }
+            bitmap |= 1 << idx;
+            weight += self.public_keys[idx].weight as u16;
+            parts.push((idx, scheme.flag(), signature[1..65].to_vec()));
+        }
+        if weight < self.threshold {
+            return Err(CustomErr::new_box(&format!(
+                "signatures weight {} is less than threshold {}",
+                weight, self.threshold
+            )));
+        }
+        parts.sort_by_key(|part| part.0);
+
+        // bcs(MultiSig { sigs, bitmap, multisig_pk })
+        let mut data: Vec<u8> = vec![SignatureScheme::MultiSig.flag()];
+        data.append(&mut uleb128_encode(parts.len()));
+        for (_, flag, sig) in parts {
+            data.push(flag);
+            data.extend_from_slice(&sig);
+        }
+        data.extend_from_slice(&bitmap.to_le_bytes());
+        data.append(&mut uleb128_encode(self.public_keys.len()));
+        for key in &self.public_keys {
+            data.push(key.scheme.flag());
+            data.extend_from_slice(&key.public_key);
+            data.push(key.weight);
+        }
+        data.extend_from_slice(&self.threshold.to_le_bytes());
+        Ok(data)
+    }
+
+    pub fn sign_with_accounts(
+        &self,
+        tx_bytes: &str,
+        accounts: &[&SuiAccount],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let signatures: Vec<Vec<u8>> = accounts
+            .iter()
+            .map(|account| account.sign_data(tx_bytes, IntentScope::TransactionData))
+            .collect();
+        self.combine_signatures(&signatures)
+    }
+
+    pub fn signed_transaction_payload(
+        &self,
+        unsafe_transaction: &UnsafeTransactionResult,
+        signatures: &[Vec<u8>],
+    ) -> Result<Payload, Box<dyn Error>> {
+        let multisig = self.combine_signatures(signatures)?;
+        Ok(Payload::safe_transaction_block_payload(
+            &unsafe_transaction.tx_bytes,
+            &base64_encode(&multisig),
+        ))
+    }
+}
+
+fn public_key_length(scheme: SignatureScheme) -> Result<usize, Box<dyn Error>> {
+    match scheme {
+        SignatureScheme::ED25519 => Ok(32),
+        SignatureScheme::Secp256k1 | SignatureScheme::Secp256r1 => Ok(33),
+        SignatureScheme::MultiSig => Err(CustomErr::new_box("multisig can not be nested")),
+    }
+}
+
+struct BcsReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BcsReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        if self.offset + len > self.data.len() {
+            return Err(CustomErr::new_box("multisig bytes are truncated"));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Box<dyn Error>> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_length(&mut self) -> Result<usize, Box<dyn Error>> {
+        match uleb128_decode(&self.data[self.offset..]) {
+            Some((value, used)) => {
+                self.offset += used;
+                Ok(value)
+            }
+            None => Err(CustomErr::new_box("invalid uleb128 length")),
+        }
+    }
+}
+
+// 解析 combine_signatures 的结果，返回 multisig 账号和每个签名成员的序号及 flag || sig || pubkey
+#[allow(clippy::type_complexity)]
+pub fn decode_multisig(
+    data: &[u8],
+) -> Result<(MultiSigAccount, Vec<(usize, Vec<u8>)>), Box<dyn Error>> {
+    let mut reader = BcsReader { data, offset: 0 };
+    if reader.read_u8()? != SignatureScheme::MultiSig.flag() {
+        return Err(CustomErr::new_box("signature is not a multisig"));
+    }
+    let mut parts: Vec<(SignatureScheme, Vec<u8>)> = vec![];
+    for _ in 0..reader.read_length()? {
+        let scheme = SignatureScheme::from_flag(reader.read_u8()?)?;
+        parts.push((scheme, reader.take(64)?.to_vec()));
+    }
+    let bitmap = reader.read_u16()?;
+    let mut public_keys: Vec<WeightedPublicKey> = vec![];
+    for _ in 0..reader.read_length()? {
+        let scheme = SignatureScheme::from_flag(reader.read_u8()?)?;
+        let public_key = reader.take(public_key_length(scheme)?)?.to_vec();
+        public_keys.push(WeightedPublicKey::new(
+            scheme,
+            public_key,
+            reader.read_u8()?,
+        ));
+    }
+    let threshold = reader.read_u16()?;
+    if reader.offset != data.len() {
+        return Err(CustomErr::new_box("unexpected trailing multisig bytes"));
+    }
+    let multisig = MultiSigAccount::new(public_keys, threshold)?;
+
+    let indexes: Vec<usize> = (0..multisig.public_keys.len())
+        .filter(|idx| bitmap & (1 << idx) != 0)
+        .collect();
+    if indexes.len() != parts.len() || bitmap >> multisig.public_keys.len() != 0 {
+        return Err(CustomErr::new_box(
+            "multisig bitmap does not match signatures",
+        ));
+    }
+    let mut signatures = vec![];
+    for (idx, (scheme, sig)) in indexes.into_iter().zip(parts) {
+        let key = &multisig.public_keys[idx];
+        if key.scheme != scheme {
+            return Err(CustomErr::new_box("multisig signature scheme mismatch"));
+        }
+        let mut signature = vec![scheme.flag()];
+        signature.extend_from_slice(&sig);
+        signature.extend_from_slice(&key.public_key);
+        signatures.push((idx, signature));
+    }
+    Ok((multisig, signatures))
+}
+
+impl Display for MultiSigAccount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sui multisig account: {} ({} keys, threshold {})",
+            &self.to_address(),
+            self.public_keys.len(),
+            self.threshold
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members() -> Vec<SuiAccount> {
+        vec![
+            SuiAccount::new_account(),
+            SuiAccount::generate(SignatureScheme::Secp256k1).unwrap(),
+            SuiAccount::generate(SignatureScheme::Secp256r1).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_multisig_two_of_three() {
+        let accounts = members();
+        let multisig = MultiSigAccount::new(
+            accounts
+                .iter()
+                .map(|account| WeightedPublicKey::from_account(account, 1))
+                .collect(),
+            2,
+        )
+        .unwrap();
+
+        let tx_bytes = base64_encode(&[1u8, 2, 3]);
+        assert!(multisig
+            .sign_with_accounts(&tx_bytes, &[&accounts[0]])
+            .is_err());
+
+        // 签名顺序与公钥顺序无关
+        let combined = multisig
+            .sign_with_accounts(&tx_bytes, &[&accounts[2], &accounts[0]])
+            .unwrap();
+        assert_eq!(combined[0], SignatureScheme::MultiSig.flag());
+        assert_eq!(combined[1], 2);
+        assert_eq!(combined[2], SignatureScheme::ED25519.flag());
+        assert_eq!(combined[67], SignatureScheme::Secp256r1.flag());
+        assert_eq!(combined[132..134].to_vec(), vec![0b101, 0]);
+        assert_eq!(combined[combined.len() - 2..].to_vec(), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_multisig_rejects_invalid_threshold() {
+        let accounts = members();
+        let keys = accounts
+            .iter()
+            .map(|account| WeightedPublicKey::from_account(account, 1))
+            .collect();
+        assert!(MultiSigAccount::new(keys, 4).is_err());
+    }
+}
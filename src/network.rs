@@ -1,12 +1,24 @@
-use crate::utils::CustomErr;
-use std::{env, error::Error, fmt::Display};
+use crate::{
+    config::ClientConfig,
+    explorer::{self, Explorer, LinkKind},
+    utils::CustomErr,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{env, error::Error, fmt::Display, str::FromStr};
 
 const NETWORK_ENV_NAME: &str = "network";
 
+// sui_getChainIdentifier 的返回值，即 genesis checkpoint digest 的前 4 个字节
+pub const MAINNET_CHAIN_IDENTIFIER: &str = "35834a8a";
+pub const TESTNET_CHAIN_IDENTIFIER: &str = "4c78adac";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Network {
     Testnet,
     Mainnet,
     Devnet,
+    // sui start 启动的本地网络，rpc 端口 9000，faucet 端口 9123
+    Localnet,
     Custom(String),
 }
 
@@ -14,69 +26,152 @@ pub fn default() -> Network {
     from_env()
 }
 
+// 优先使用 env network，其次使用 sui cli client.yaml 中的 active_env，最后默认 mainnet
 pub fn from_env() -> Network {
     if env::var_os(NETWORK_ENV_NAME).is_some() {
         if let Ok(value) = env::var(NETWORK_ENV_NAME) {
             return Network::from_name(value);
         }
     }
+    if let Some(config) = ClientConfig::load_default() {
+        if let Ok(network) = config.network() {
+            return network;
+        }
+    }
     Network::Mainnet
 }
 
 impl Network {
     pub fn from_name(name: String) -> Self {
-        if name.eq("testnet") {
-            return Network::Testnet;
-        } else if name.eq("devnet") {
-            return Network::Devnet;
-        } else if name.eq("mainnet") {
-            return Network::Mainnet;
-        } else {
-            return Network::Custom(name);
+        match name.as_str() {
+            "testnet" => Network::Testnet,
+            "devnet" => Network::Devnet,
+            "mainnet" => Network::Mainnet,
+            "localnet" => Network::Localnet,
+            _ => Network::Custom(name),
         }
     }
 
+    // 官方 fullnode 地址对应到具体的网络，其他地址作为 Custom
+    pub fn from_rpc(url: &str) -> Self {
+        let url = url.trim_end_matches('/');
+        [
+            Network::Mainnet,
+            Network::Testnet,
+            Network::Devnet,
+            Network::Localnet,
+        ]
+        .into_iter()
+        .find(|network| network.get_gateway() == url)
+        .unwrap_or_else(|| Network::Custom(url.to_string()))
+    }
+
     pub fn get_gateway(&self) -> String {
         match self {
             Network::Testnet => String::from("https://fullnode.testnet.sui.io:443"),
             Network::Mainnet => String::from("https://fullnode.mainnet.sui.io:443"),
             Network::Devnet => String::from("https://fullnode.devnet.sui.io:443"),
+            Network::Localnet => String::from("http://127.0.0.1:9000"),
             Network::Custom(url) => url.clone(),
         }
     }
 
+    // 订阅使用的 websocket 地址，与 rpc 地址相同，协议改为 ws / wss
+    pub fn get_ws_url(&self) -> String {
+        let gateway = self.get_gateway();
+        match gateway.strip_prefix("https://") {
+            Some(rest) => format!("wss://{}", rest),
+            None => gateway.replacen("http://", "ws://", 1),
+        }
+    }
+
     pub fn faucet_url(&self) -> Result<String, Box<dyn Error>> {
         match self {
             Network::Devnet => Ok("https://faucet.devnet.sui.io/gas".to_string()),
             Network::Testnet => Ok("https://faucet.testnet.sui.io/gas".to_string()),
             Network::Mainnet => Err(Box::new(CustomErr::new("mainnet does not support faucet"))),
+            Network::Localnet => Ok("http://127.0.0.1:9123/gas".to_string()),
             Network::Custom(url) => Ok(format!("{}/gas", url)),
         }
     }
 
+    // devnet 和 localnet 会被重置，没有固定的 chain identifier
+    pub fn chain_identifier(&self) -> Option<&'static str> {
+        match self {
+            Network::Mainnet => Some(MAINNET_CHAIN_IDENTIFIER),
+            Network::Testnet => Some(TESTNET_CHAIN_IDENTIFIER),
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         match self {
             Network::Testnet => String::from("testnet"),
             Network::Mainnet => String::from("mainnet"),
             Network::Devnet => String::from("devnet"),
+            Network::Localnet => String::from("localnet"),
             Network::Custom(url) => url.clone(),
         }
     }
 
-    pub fn object_link(&self, object_id: &String) -> String {
-        format!(
-            "https://suiexplorer.com/object/{}?network={}",
-            object_id,
-            self.to_string()
-        )
+    // explorer 来自 env explorer_{network} 或者 env explorer，默认为 suiscan
+    pub fn explorer(&self) -> Explorer {
+        explorer::for_network(self)
+    }
+
+    pub fn explorer_link(&self, explorer: &Explorer, kind: LinkKind, id: &str) -> Option<String> {
+        explorer.link(self, kind, id)
+    }
+
+    pub fn object_link(&self, object_id: &str) -> Option<String> {
+        self.explorer().link(self, LinkKind::Object, object_id)
+    }
+
+    pub fn transaction_link(&self, digest: &str) -> Option<String> {
+        self.explorer().link(self, LinkKind::Transaction, digest)
+    }
+
+    pub fn address_link(&self, address: &str) -> Option<String> {
+        self.explorer().link(self, LinkKind::Address, address)
     }
 
-    pub fn transaction_link(&self, digest: &String) -> String {
-        format!(
-            "https://suiexplorer.com/txblock/{}?network={}",
-            digest,
-            self.to_string()
-        )
+    pub fn package_link(&self, package_id: &str) -> Option<String> {
+        self.explorer().link(self, LinkKind::Package, package_id)
+    }
+
+    pub fn checkpoint_link(&self, sequence_number: u64) -> Option<String> {
+        self.explorer()
+            .link(self, LinkKind::Checkpoint, &sequence_number.to_string())
+    }
+}
+
+// 支持 testnet / mainnet / devnet / localnet 以及 http(s) 开头的 rpc 地址
+impl FromStr for Network {
+    type Err = CustomErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.starts_with("http://") || value.starts_with("https://") {
+            return Ok(Network::from_rpc(value));
+        }
+        match Network::from_name(value.to_lowercase()) {
+            Network::Custom(_) => Err(CustomErr::new(&format!("unknown network : {}", value))),
+            network => Ok(network),
+        }
+    }
+}
+
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -85,3 +180,34 @@ impl Display for Network {
         write!(f, "[{}, {}]", self.to_string(), self.get_gateway())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_network() {
+        assert_eq!("localnet".parse::<Network>().unwrap(), Network::Localnet);
+        assert_eq!("Testnet".parse::<Network>().unwrap(), Network::Testnet);
+        assert_eq!(
+            "http://127.0.0.1:9000".parse::<Network>().unwrap(),
+            Network::Localnet
+        );
+        assert_eq!(
+            "https://rpc.example.com".parse::<Network>().unwrap(),
+            Network::Custom("https://rpc.example.com".to_string())
+        );
+        assert!("moonnet".parse::<Network>().is_err());
+
+        let networks: Vec<Network> =
+            serde_json::from_str(r#"["mainnet","localnet","https://rpc.example.com"]"#).unwrap();
+        assert_eq!(
+            networks[1].faucet_url().unwrap(),
+            "http://127.0.0.1:9123/gas"
+        );
+        assert_eq!(
+            serde_json::to_string(&networks).unwrap(),
+            r#"["mainnet","localnet","https://rpc.example.com"]"#
+        );
+    }
+}
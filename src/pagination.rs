@@ -0,0 +1,116 @@
+use crate::error::SuiError;
+use futures::{
+    stream::{self, Stream, StreamExt, TryStreamExt},
+    Future,
+};
+
+// cursor 分页返回的数据，例如 suix_getOwnedObjects 、suix_getCoins
+pub trait Paginated {
+    type Item;
+    type Cursor;
+
+    // 返回 (当前页的数据, next_cursor, has_next_page)
+    fn into_page(self) -> (Vec<Self::Item>, Option<Self::Cursor>, bool);
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PageOptions {
+    // 每次请求的 limit，None 时使用节点的默认值
+    pub page_size: Option<u64>,
+    // 最多返回的数据条数，None 时遍历所有页
+    pub max_items: Option<usize>,
+}
+
+impl PageOptions {
+    pub fn new(page_size: Option<u64>, max_items: Option<usize>) -> Self {
+        Self {
+            page_size,
+            max_items,
+        }
+    }
+}
+
+// fetch 根据 (cursor, limit) 获取一页数据，返回逐条数据的 stream
+pub fn paginate<'a, P, F, Fut>(
+    fetch: F,
+    options: PageOptions,
+) -> impl Stream<Item = Result<P::Item, SuiError>> + 'a
+where
+    P: Paginated + 'a,
+    P::Item: 'a,
+    P::Cursor: 'a,
+    F: FnMut(Option<P::Cursor>, Option<u64>) -> Fut + 'a,
+    Fut: Future<Output = Result<P, SuiError>> + 'a,
+{
+    // state 为 None 时表示已经没有下一页
+    let pages = stream::try_unfold((Some(None), fetch), move |(state, mut fetch)| async move {
+        let cursor = match state {
+            Some(cursor) => cursor,
+            None => return Ok(None),
+        };
+        let (items, next_cursor, has_next_page) =
+            fetch(cursor, options.page_size).await?.into_page();
+        let next_state = match next_cursor {
+            Some(next_cursor) if has_next_page => Some(Some(next_cursor)),
+            _ => None,
+        };
+        Ok::<_, SuiError>(Some((items, (next_state, fetch))))
+    });
+    let items = pages
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok::<_, SuiError>)))
+        .try_flatten();
+    items.take(options.max_items.unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NumberPage(Vec<u64>, Option<usize>, bool);
+
+    impl Paginated for NumberPage {
+        type Item = u64;
+        type Cursor = usize;
+
+        fn into_page(self) -> (Vec<u64>, Option<usize>, bool) {
+            (self.0, self.1, self.2)
+        }
+    }
+
+    // 0..25 的数据，每页 limit 条
+    async fn fetch_numbers(
+        cursor: Option<usize>,
+        limit: Option<u64>,
+    ) -> Result<NumberPage, SuiError> {
+        let start = cursor.unwrap_or(0);
+        let end = (start + limit.unwrap_or(10) as usize).min(25);
+        Ok(NumberPage(
+            (start as u64..end as u64).collect(),
+            Some(end),
+            end < 25,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let all: Vec<u64> = paginate(fetch_numbers, PageOptions::default())
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+        assert_eq!(all, (0..25).collect::<Vec<u64>>());
+
+        let mut calls = 0;
+        let first: Vec<u64> = paginate(
+            |cursor, limit| {
+                calls += 1;
+                fetch_numbers(cursor, limit)
+            },
+            PageOptions::new(Some(4), Some(6)),
+        )
+        .map(|item| item.unwrap())
+        .collect()
+        .await;
+        assert_eq!(first, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(calls, 2);
+    }
+}
@@ -1,15 +1,36 @@
+use crate::response::EventId;
 use crate::utils::current_timestamp;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, to_value, Value};
 use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::vec::Vec;
 
 pub const VERSION: &str = "0.0.0";
-pub const PAYLOAD_JSONRPC_VERSION: &str = &"2.0";
+pub const PAYLOAD_JSONRPC_VERSION: &str = "2.0";
 
-#[derive(Serialize, Deserialize)]
+static LAST_PAYLOAD_ID: AtomicU64 = AtomicU64::new(0);
+
+// 以毫秒时间戳为基础，同一毫秒内创建的 payload 依次加一，保证 id 不重复
+fn next_payload_id() -> u64 {
+    let mut last = LAST_PAYLOAD_ID.load(Ordering::Relaxed);
+    loop {
+        let next = current_timestamp().max(last + 1);
+        match LAST_PAYLOAD_ID.compare_exchange_weak(
+            last,
+            next,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => return next,
+            Err(current) => last = current,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct TransactionBlockResponseOptions {
+pub struct TransactionBlockResponseOptions {
     show_input: bool,
     show_raw_input: bool,
     show_effects: bool,
@@ -40,9 +61,20 @@ impl TransactionBlockResponseOptions {
     pub fn default_options() -> Self {
         Self::new(true, true, true, true, true, true)
     }
+
+    // 只返回 effects 和 events
+    pub fn effects_only() -> Self {
+        Self::new(false, false, true, true, false, false)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+impl Default for TransactionBlockResponseOptions {
+    fn default() -> Self {
+        Self::default_options()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterOption {
     show_type: bool,
@@ -98,9 +130,9 @@ impl Display for Payload {
 
 pub struct TypeArguments(Vec<String>);
 
-impl Into<Vec<Value>> for TypeArguments {
-    fn into(self) -> Vec<Value> {
-        self.0.into_iter().map(|s| Value::String(s)).collect()
+impl From<TypeArguments> for Vec<Value> {
+    fn from(arguments: TypeArguments) -> Self {
+        arguments.0.into_iter().map(Value::String).collect()
     }
 }
 
@@ -108,12 +140,20 @@ impl Payload {
     pub fn build(method: String, params: Vec<Value>) -> Self {
         Self {
             jsonrpc: String::from(PAYLOAD_JSONRPC_VERSION),
-            id: current_timestamp(),
+            id: next_payload_id(),
             method,
             params,
         }
     }
 
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
     pub fn method_paylod(method: String) -> Self {
         Self::build(method, vec![])
     }
@@ -128,6 +168,94 @@ impl Payload {
         )
     }
 
+    pub fn get_transaction_block(digest: &str, option: &TransactionBlockResponseOptions) -> Self {
+        Self::build(
+            String::from("sui_getTransactionBlock"),
+            vec![Value::String(digest.to_string()), to_value(option).unwrap()],
+        )
+    }
+
+    pub fn multi_get_transaction_blocks(
+        digests: &[String],
+        option: &TransactionBlockResponseOptions,
+    ) -> Self {
+        Self::build(
+            String::from("sui_multiGetTransactionBlocks"),
+            vec![Value::from(digests.to_vec()), to_value(option).unwrap()],
+        )
+    }
+
+    pub fn multi_get_objects(object_ids: &[String], option: &FilterOption) -> Self {
+        Self::build(
+            String::from("sui_multiGetObjects"),
+            vec![Value::from(object_ids.to_vec()), to_value(option).unwrap()],
+        )
+    }
+
+    pub fn try_get_past_object(object_id: &str, version: u64, option: &FilterOption) -> Self {
+        Self::build(
+            String::from("sui_tryGetPastObject"),
+            vec![
+                Value::String(object_id.to_string()),
+                Value::from(version),
+                to_value(option).unwrap(),
+            ],
+        )
+    }
+
+    pub fn get_total_transaction_blocks() -> Self {
+        Self::method_paylod(String::from("sui_getTotalTransactionBlocks"))
+    }
+
+    // coin_type 为 None 时节点默认查询 0x2::sui::SUI
+    pub fn get_balance(owner_address: &str, coin_type: Option<&str>) -> Self {
+        Self::build(
+            String::from("suix_getBalance"),
+            vec![
+                Value::String(owner_address.to_string()),
+                match coin_type {
+                    None => Value::Null,
+                    Some(v) => Value::String(v.to_string()),
+                },
+            ],
+        )
+    }
+
+    pub fn get_reference_gas_price() -> Self {
+        Self::method_paylod(String::from("suix_getReferenceGasPrice"))
+    }
+
+    pub fn get_total_supply(coin_type: &str) -> Self {
+        Self::build(
+            String::from("suix_getTotalSupply"),
+            vec![Value::String(coin_type.to_string())],
+        )
+    }
+
+    // cursor 为上一页返回的 next_cursor，descending 为 true 时从最新的 event 开始
+    pub fn query_events(
+        filter: &EventFilter,
+        cursor: Option<&EventId>,
+        limit: Option<u64>,
+        descending: bool,
+    ) -> Self {
+        Self::build(
+            String::from("suix_queryEvents"),
+            vec![
+                to_value(filter).unwrap(),
+                match cursor {
+                    None => Value::Null,
+                    Some(v) => to_value(v).unwrap(),
+                },
+                match limit {
+                    None => Value::Null,
+                    Some(v) => Value::from(v),
+                },
+                Value::Bool(descending),
+            ],
+        )
+    }
+
     pub fn unsafe_transfer_object(
         owner_address: &str,
         object_id: &str,
@@ -147,6 +275,37 @@ impl Payload {
         )
     }
 
+    pub fn dry_run_transaction_block(tx_bytes: &str) -> Self {
+        Self::build(
+            String::from("sui_dryRunTransactionBlock"),
+            vec![Value::String(tx_bytes.to_string())],
+        )
+    }
+
+    // tx_kind 为 base64 编码的 TransactionKind，不包含 gas 信息
+    pub fn dev_inspect_transaction_block(
+        sender: &str,
+        tx_kind: &str,
+        gas_price: Option<u64>,
+        epoch: Option<u64>,
+    ) -> Self {
+        Self::build(
+            String::from("sui_devInspectTransactionBlock"),
+            vec![
+                Value::String(sender.to_string()),
+                Value::String(tx_kind.to_string()),
+                match gas_price {
+                    None => Value::Null,
+                    Some(v) => Value::String(v.to_string()),
+                },
+                match epoch {
+                    None => Value::Null,
+                    Some(v) => Value::String(v.to_string()),
+                },
+            ],
+        )
+    }
+
     pub fn safe_transaction_block_payload(tx_bytes: &str, signatures: &str) -> Self {
         let option = TransactionBlockResponseOptions::default_options();
         Self::build(
@@ -179,6 +338,7 @@ impl Payload {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn move_call(
         owner_address: String,
         package_object_id: String,
@@ -223,21 +383,12 @@ pub fn new_faucet(recipient: String) -> FaucetInfo {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct QueryOption {
     options: FilterOption,
     filter: QueryFilter,
 }
 
-impl Default for QueryOption {
-    fn default() -> Self {
-        Self {
-            options: FilterOption::default(),
-            filter: QueryFilter::default(),
-        }
-    }
-}
-
 impl QueryOption {
     pub fn with_options(options: FilterOption) -> Self {
         Self {
@@ -268,7 +419,7 @@ impl QueryOption {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum QueryFilter {
     MatchAll(Vec<QueryFilter>),
     MatchAny(Vec<QueryFilter>),
@@ -287,3 +438,121 @@ impl Default for QueryFilter {
         QueryFilter::MatchNone(vec![])
     }
 }
+
+// suix_queryEvents 的过滤条件
+#[derive(Clone, Serialize, Deserialize)]
+pub enum EventFilter {
+    Sender(String),
+    Transaction(String),
+    Package(String),
+    MoveModule {
+        package: String,
+        module: String,
+    },
+    MoveEventType(String),
+    // 毫秒时间戳
+    #[serde(rename_all = "camelCase")]
+    TimeRange {
+        start_time: String,
+        end_time: String,
+    },
+    All(Vec<EventFilter>),
+    Any(Vec<EventFilter>),
+    And(Box<EventFilter>, Box<EventFilter>),
+    Or(Box<EventFilter>, Box<EventFilter>),
+}
+
+impl EventFilter {
+    pub fn sender(address: &str) -> Self {
+        EventFilter::Sender(address.to_string())
+    }
+
+    pub fn transaction(digest: &str) -> Self {
+        EventFilter::Transaction(digest.to_string())
+    }
+
+    pub fn package(package: &str) -> Self {
+        EventFilter::Package(package.to_string())
+    }
+
+    pub fn module(package: &str, module: &str) -> Self {
+        EventFilter::MoveModule {
+            package: package.to_string(),
+            module: module.to_string(),
+        }
+    }
+
+    pub fn event_type(event_type: &str) -> Self {
+        EventFilter::MoveEventType(event_type.to_string())
+    }
+
+    pub fn time_range(start_ms: u64, end_ms: u64) -> Self {
+        EventFilter::TimeRange {
+            start_time: start_ms.to_string(),
+            end_time: end_ms.to_string(),
+        }
+    }
+
+    pub fn and(self, other: EventFilter) -> Self {
+        EventFilter::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: EventFilter) -> Self {
+        EventFilter::Or(Box::new(self), Box::new(other))
+    }
+}
+
+// suix_subscribeTransaction 的过滤条件
+#[derive(Clone, Serialize, Deserialize)]
+pub enum TransactionFilter {
+    Checkpoint(String),
+    MoveFunction {
+        package: String,
+        module: Option<String>,
+        function: Option<String>,
+    },
+    InputObject(String),
+    ChangedObject(String),
+    FromAddress(String),
+    ToAddress(String),
+    FromAndToAddress {
+        from: String,
+        to: String,
+    },
+    FromOrToAddress {
+        addr: String,
+    },
+    TransactionKind(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_id_unique() {
+        let payloads: Vec<Payload> = (0..1000)
+            .map(|_| Payload::method_paylod("sui_getChainIdentifier".to_string()))
+            .collect();
+        let ids: std::collections::HashSet<u64> =
+            payloads.iter().map(|payload| payload.id()).collect();
+        assert_eq!(ids.len(), payloads.len());
+    }
+
+    #[test]
+    fn test_event_filter_json() {
+        let filter = EventFilter::module("0x2", "coin")
+            .and(EventFilter::time_range(1, 2))
+            .or(EventFilter::All(vec![EventFilter::sender("0x1")]));
+        assert_eq!(
+            to_value(&filter).unwrap(),
+            json!({"Or": [
+                {"And": [
+                    {"MoveModule": {"package": "0x2", "module": "coin"}},
+                    {"TimeRange": {"startTime": "1", "endTime": "2"}}
+                ]},
+                {"All": [{"Sender": "0x1"}]}
+            ]})
+        );
+    }
+}
@@ -1,7 +1,10 @@
-use crate::{account::SuiAccount, client::Client};
+use crate::{
+    account::SuiAccount, client::Client, error::SuiError, pagination::Paginated,
+    utils::base64_decode,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{default::Default, error::Error, fmt::Display, vec};
+use std::{default::Default, fmt::Display, vec};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
@@ -11,17 +14,23 @@ pub enum Either<A, B> {
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct JsonResult<T: Default> {
+pub struct JsonResult<T> {
     pub jsonrpc: String,
-    #[serde(default)]
-    pub result: T,
+    pub result: Option<T>,
     pub error: Option<RpcError>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct RpcError {
-    code: i32,
-    message: String,
+    pub code: i32,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rpc error {} : {}", self.code, self.message)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,22 +38,65 @@ pub struct SimpleObject {
     pub data: ObjectData,
 }
 
+// 除了 object_id 、version 、digest 以外的字段都由 FilterOption 决定是否返回
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectData {
     pub object_id: String,
-    version: String,
-    digest: String,
+    pub version: String,
+    pub digest: String,
     #[serde(rename = "type")]
-    object_type: String,
-    owner: Owner,
-    previous_transaction: String,
     #[serde(default)]
-    storage_rebate: String,
-    content: ObjectContent,
+    pub object_type: String,
+    #[serde(default)]
+    pub owner: Owner,
+    #[serde(default)]
+    pub previous_transaction: String,
+    #[serde(default)]
+    pub storage_rebate: String,
+    #[serde(default)]
+    pub content: ObjectContent,
 }
 
+// sui_multiGetObjects 中不存在或者已删除的 object 只有 error
+#[derive(Serialize, Deserialize, Default)]
+pub struct ObjectResponse {
+    pub data: Option<ObjectData>,
+    pub error: Option<Value>,
+}
+
+// sui_tryGetPastObject 的返回，status 对应不同的 details
 #[derive(Serialize, Deserialize)]
+#[serde(tag = "status", content = "details")]
+pub enum PastObjectResponse {
+    VersionFound(ObjectData),
+    ObjectNotExists(String),
+    ObjectDeleted(MiniObject),
+    // [object_id, version]
+    VersionNotFound(String, u64),
+    VersionTooHigh {
+        object_id: String,
+        asked_version: u64,
+        latest_version: u64,
+    },
+}
+
+impl Default for PastObjectResponse {
+    fn default() -> Self {
+        PastObjectResponse::ObjectNotExists(String::from(""))
+    }
+}
+
+impl PastObjectResponse {
+    pub fn object(&self) -> Option<&ObjectData> {
+        match self {
+            PastObjectResponse::VersionFound(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
 pub struct Owner {
     #[serde(rename = "ObjectOwner")]
     #[serde(default)]
@@ -60,19 +112,25 @@ pub struct AddressOwner {
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectContent {
-    data_type: String,
+    pub data_type: String,
     #[serde(rename = "type")]
-    object_type: String,
-    has_public_transfer: bool,
-    fields: Value,
+    #[serde(default)]
+    pub object_type: String,
+    #[serde(default)]
+    pub has_public_transfer: bool,
+    #[serde(default)]
+    pub fields: Value,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct UnsafeTransactionResult {
     pub tx_bytes: String,
     gas: Vec<MiniObject>,
     input_objects: Vec<InputObject>,
+    // 为 true 时签名前先 dry run，失败则不签名
+    #[serde(skip)]
+    dry_run: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -87,12 +145,12 @@ struct MovePackage {
     move_package: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct MiniObject {
-    object_id: String,
-    version: u64,
-    digest: String,
+    pub object_id: String,
+    pub version: u64,
+    pub digest: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -106,7 +164,8 @@ pub struct ImmOrOwnedMoveObject {
 #[serde(rename_all = "camelCase")]
 pub struct TransactionEffectResult {
     pub digest: String,
-    pub events: Vec<Value>,
+    #[serde(default)]
+    pub events: Vec<SuiEvent>,
     raw_transaction: String,
     transaction: Value,
     pub effects: Option<TransactionEffects>,
@@ -124,27 +183,115 @@ impl Default for TransactionEffectResult {
     }
 }
 
-impl Default for UnsafeTransactionResult {
-    fn default() -> Self {
-        Self {
-            tx_bytes: String::from(""),
-            gas: vec![],
-            input_objects: vec![],
-        }
+impl UnsafeTransactionResult {
+    pub fn set_dry_run(&mut self) {
+        self.dry_run = true;
     }
-}
 
-impl UnsafeTransactionResult {
-    pub async fn with_signed_execute<'a>(
+    pub async fn dry_run(&self, client: &Client) -> Result<DryRunResult, SuiError> {
+        client.dry_run_transaction_block(&self.tx_bytes).await
+    }
+
+    pub async fn with_signed_execute(
         &self,
         client: &Client,
         account: &SuiAccount,
-    ) -> Result<JsonResult<TransactionEffectResult>, Box<dyn Error>> {
-        let payload = account.sign_unsafe_transaciton(&self);
+    ) -> Result<TransactionEffectResult, SuiError> {
+        // 开启 chain guard 时，gateway 所在的链与预期不一致则不签名
+        client.check_chain().await?;
+        match base64_decode(&self.tx_bytes) {
+            Ok(tx_data) if !tx_data.is_empty() => {}
+            _ => {
+                return Err(SuiError::Signing(
+                    "tx_bytes is empty or not valid base64".to_string(),
+                ))
+            }
+        }
+        if self.dry_run {
+            if let Some(error) = self.dry_run(client).await?.failure() {
+                return Err(SuiError::DryRun(error));
+            }
+        }
+        let payload = account.sign_unsafe_transaciton(self);
         client.send_payload_effect(&payload).await
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventId {
+    pub tx_digest: String,
+    pub event_seq: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiEvent {
+    pub id: EventId,
+    #[serde(default)]
+    pub package_id: String,
+    #[serde(default)]
+    pub transaction_module: String,
+    #[serde(default)]
+    pub sender: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(default)]
+    pub parsed_json: Value,
+    // base58 编码的 bcs 数据
+    #[serde(default)]
+    pub bcs: String,
+    pub timestamp_ms: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPage {
+    pub data: Vec<SuiEvent>,
+    pub next_cursor: Option<EventId>,
+    pub has_next_page: bool,
+}
+
+impl Paginated for EventPage {
+    type Item = SuiEvent;
+    type Cursor = EventId;
+
+    fn into_page(self) -> (Vec<SuiEvent>, Option<EventId>, bool) {
+        (self.data, self.next_cursor, self.has_next_page)
+    }
+}
+
+// sui_getTransactionBlock 的返回，字段由 TransactionBlockResponseOptions 决定是否返回
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionBlockResponse {
+    pub digest: String,
+    pub transaction: Option<Value>,
+    pub raw_transaction: Option<String>,
+    pub effects: Option<TransactionEffects>,
+    pub events: Option<Vec<SuiEvent>>,
+    pub object_changes: Option<Vec<Value>>,
+    pub balance_changes: Option<Vec<BalanceChange>>,
+    pub timestamp_ms: Option<String>,
+    pub checkpoint: Option<String>,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub owner: Value,
+    pub coin_type: String,
+    // 负数表示减少
+    pub amount: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Supply {
+    pub value: String,
+}
+
 impl Default for SimpleObject {
     fn default() -> Self {
         Self {
@@ -162,14 +309,6 @@ impl Default for SimpleObject {
     }
 }
 
-impl Default for Owner {
-    fn default() -> Self {
-        Self {
-            object_owner: String::from(""),
-        }
-    }
-}
-
 impl Default for ObjectContent {
     fn default() -> Self {
         Self {
@@ -181,22 +320,12 @@ impl Default for ObjectContent {
     }
 }
 
-impl Default for MiniObject {
-    fn default() -> Self {
-        Self {
-            object_id: String::from(""),
-            version: 0,
-            digest: String::from(""),
-        }
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectList {
     pub data: Vec<SimpleObject>,
-    next_cursor: Option<String>,
-    has_next_page: bool,
+    pub next_cursor: Option<String>,
+    pub has_next_page: bool,
 }
 
 impl Default for ObjectList {
@@ -209,7 +338,7 @@ impl Default for ObjectList {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Balance {
     pub coin_type: String,
@@ -218,13 +347,35 @@ pub struct Balance {
     pub locked_balance: Value,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CoinList {
     pub data: Vec<CoinInfo>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_next_page: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+impl Paginated for ObjectList {
+    type Item = SimpleObject;
+    type Cursor = String;
+
+    fn into_page(self) -> (Vec<SimpleObject>, Option<String>, bool) {
+        (self.data, self.next_cursor, self.has_next_page)
+    }
+}
+
+impl Paginated for CoinList {
+    type Item = CoinInfo;
+    type Cursor = String;
+
+    fn into_page(self) -> (Vec<CoinInfo>, Option<String>, bool) {
+        (self.data, self.next_cursor, self.has_next_page)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CoinInfo {
     pub coin_type: String,
@@ -245,25 +396,6 @@ impl Display for CoinInfo {
     }
 }
 
-impl Default for CoinInfo {
-    fn default() -> Self {
-        Self {
-            coin_type: String::from(""),
-            coin_object_id: String::from(""),
-            version: String::from(""),
-            digest: String::from(""),
-            balance: String::from(""),
-            previous_transaction: String::from(""),
-        }
-    }
-}
-
-impl Default for CoinList {
-    fn default() -> Self {
-        Self { data: vec![] }
-    }
-}
-
 impl CoinInfo {
     pub fn balance_u64(&self) -> u64 {
         self.balance.parse::<u64>().unwrap()
@@ -273,7 +405,8 @@ impl CoinInfo {
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OwnerWithReference {
-    pub owner: Either<String, AddressOwner>,
+    // AddressOwner、ObjectOwner、Shared 或者 "Immutable"
+    pub owner: Value,
     reference: MiniObject,
 }
 
@@ -283,27 +416,42 @@ pub struct TransactionEffects {
     status: StatusMessage,
     executed_epoch: String,
     message_version: String,
+    #[serde(default)]
     dependencies: Vec<String>,
     transaction_digest: String,
     gas_object: OwnerWithReference,
+    #[serde(default)]
     modified_at_versions: Vec<ObjectVersion>,
     gas_used: GasUsed,
     created: Option<Vec<OwnerWithReference>>,
+    #[serde(default)]
     mutated: Vec<OwnerWithReference>,
 }
 
 impl TransactionEffects {
+    pub fn gas_used(&self) -> &GasUsed {
+        &self.gas_used
+    }
+
+    // 执行失败时返回错误信息
+    pub fn failure(&self) -> Option<String> {
+        if self.status.status == "success" {
+            return None;
+        }
+        Some(
+            self.status
+                .error
+                .clone()
+                .unwrap_or_else(|| self.status.status.to_string()),
+        )
+    }
+
     pub fn find_imutable_object(&self) -> Vec<String> {
         let mut items = vec![];
         if let Some(created_items) = &self.created {
             for info in created_items {
-                match &info.owner {
-                    Either::A(msg) => {
-                        if msg == "Immutable" {
-                            items.push(info.reference.object_id.to_string())
-                        }
-                    }
-                    _ => {}
+                if info.owner.as_str() == Some("Immutable") {
+                    items.push(info.reference.object_id.to_string())
                 }
             }
         }
@@ -322,13 +470,277 @@ pub struct ObjectVersion {
 #[derive(Serialize, Deserialize)]
 pub struct StatusMessage {
     status: String,
+    #[serde(default)]
+    error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GasUsed {
-    computation_cost: String,
-    storage_cost: String,
-    storage_rebate: String,
-    non_refundable_storage_fee: String,
+    pub computation_cost: String,
+    pub storage_cost: String,
+    pub storage_rebate: String,
+    pub non_refundable_storage_fee: String,
+}
+
+// sui_dryRunTransactionBlock 的返回，不需要签名，也不消耗 gas
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult {
+    pub effects: Option<TransactionEffects>,
+    #[serde(default)]
+    pub events: Vec<SuiEvent>,
+    #[serde(default)]
+    pub object_changes: Vec<ObjectChange>,
+    #[serde(default)]
+    pub balance_changes: Vec<BalanceChange>,
+    #[serde(default)]
+    pub input: Value,
+}
+
+impl DryRunResult {
+    pub fn failure(&self) -> Option<String> {
+        match &self.effects {
+            Some(effects) => effects.failure(),
+            None => Some("dry run returned no effects".to_string()),
+        }
+    }
+
+    pub fn gas_used(&self) -> Option<&GasUsed> {
+        self.effects.as_ref().map(|effects| effects.gas_used())
+    }
+}
+
+// sui_devInspectTransactionBlock 的返回，results 为每个命令的执行结果
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DevInspectResult {
+    pub effects: Option<TransactionEffects>,
+    #[serde(default)]
+    pub events: Vec<SuiEvent>,
+    pub results: Option<Vec<ExecutionResult>>,
+    pub error: Option<String>,
+}
+
+impl DevInspectResult {
+    pub fn failure(&self) -> Option<String> {
+        if let Some(error) = &self.error {
+            return Some(error.to_string());
+        }
+        self.effects.as_ref().and_then(|effects| effects.failure())
+    }
+
+    // 第 command 个命令的返回值
+    pub fn return_values(&self, command: usize) -> &[(Vec<u8>, String)] {
+        match self
+            .results
+            .as_ref()
+            .and_then(|results| results.get(command))
+        {
+            Some(result) => &result.return_values,
+            None => &[],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResult {
+    #[serde(default)]
+    pub mutable_reference_outputs: Vec<Value>,
+    // (bcs 数据, move 类型)
+    #[serde(default)]
+    pub return_values: Vec<(Vec<u8>, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ObjectChange {
+    Published {
+        package_id: String,
+        version: String,
+        digest: String,
+        modules: Vec<String>,
+    },
+    Transferred {
+        sender: String,
+        recipient: Value,
+        object_type: String,
+        object_id: String,
+        version: String,
+        digest: String,
+    },
+    Mutated {
+        sender: String,
+        owner: Value,
+        object_type: String,
+        object_id: String,
+        version: String,
+        previous_version: String,
+        digest: String,
+    },
+    Deleted {
+        sender: String,
+        object_type: String,
+        object_id: String,
+        version: String,
+    },
+    Wrapped {
+        sender: String,
+        object_type: String,
+        object_id: String,
+        version: String,
+    },
+    Created {
+        sender: String,
+        owner: Value,
+        object_type: String,
+        object_id: String,
+        version: String,
+        digest: String,
+    },
+}
+
+impl ObjectChange {
+    // Published 返回 package id
+    pub fn object_id(&self) -> &str {
+        match self {
+            ObjectChange::Published { package_id, .. } => package_id,
+            ObjectChange::Transferred { object_id, .. }
+            | ObjectChange::Mutated { object_id, .. }
+            | ObjectChange::Deleted { object_id, .. }
+            | ObjectChange::Wrapped { object_id, .. }
+            | ObjectChange::Created { object_id, .. } => object_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_past_object() {
+        let found: PastObjectResponse = serde_json::from_str(
+            r#"{"status":"VersionFound","details":{"objectId":"0x5","version":"4","digest":"abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(found.object().unwrap().version, "4");
+
+        let not_found: PastObjectResponse =
+            serde_json::from_str(r#"{"status":"VersionNotFound","details":["0x5",9]}"#).unwrap();
+        assert!(matches!(
+            not_found,
+            PastObjectResponse::VersionNotFound(_, 9)
+        ));
+
+        let too_high: PastObjectResponse = serde_json::from_str(
+            r#"{"status":"VersionTooHigh","details":{"object_id":"0x5","asked_version":9,"latest_version":4}}"#,
+        )
+        .unwrap();
+        assert!(too_high.object().is_none());
+    }
+
+    #[test]
+    fn test_parse_multi_get() {
+        let objects: Vec<ObjectResponse> = serde_json::from_str(
+            r#"[{"data":{"objectId":"0x5","version":"4","digest":"abc","type":"0x2::coin::Coin<0x2::sui::SUI>"}},
+                {"error":{"code":"notExists","object_id":"0x6"}}]"#,
+        )
+        .unwrap();
+        assert_eq!(objects[0].data.as_ref().unwrap().object_id, "0x5");
+        assert!(objects[1].data.is_none());
+
+        let transactions: Vec<TransactionBlockResponse> = serde_json::from_str(
+            r#"[{"digest":"abc","timestampMs":"1690000000000","checkpoint":"12",
+                 "balanceChanges":[{"owner":{"AddressOwner":"0x1"},"coinType":"0x2::sui::SUI","amount":"-100"}]},
+                {"digest":"def","effects":{"messageVersion":"v1","status":{"status":"success"},"executedEpoch":"5",
+                 "gasUsed":{"computationCost":"750000","storageCost":"2432000","storageRebate":"2407680","nonRefundableStorageFee":"24320"},
+                 "modifiedAtVersions":[{"objectId":"0x6","sequenceNumber":"8"},{"objectId":"0x1","sequenceNumber":"8"}],
+                 "sharedObjects":[{"objectId":"0x6","version":8,"digest":"ghi"}],"transactionDigest":"def",
+                 "mutated":[{"owner":{"Shared":{"initial_shared_version":3}},"reference":{"objectId":"0x6","version":9,"digest":"jkl"}},
+                            {"owner":{"AddressOwner":"0x1"},"reference":{"objectId":"0x1","version":9,"digest":"mno"}}],
+                 "gasObject":{"owner":{"AddressOwner":"0x1"},"reference":{"objectId":"0x1","version":9,"digest":"mno"}},
+                 "eventsDigest":"pqr","dependencies":["stu"]}}]"#,
+        )
+        .unwrap();
+        assert_eq!(transactions[0].checkpoint.as_deref(), Some("12"));
+        assert_eq!(
+            transactions[0].balance_changes.as_ref().unwrap()[0].amount,
+            "-100"
+        );
+        assert!(transactions[0].effects.is_none());
+        let effects = transactions[1].effects.as_ref().unwrap();
+        assert!(effects.failure().is_none());
+        assert_eq!(effects.gas_used().computation_cost, "750000");
+        assert_eq!(
+            effects.mutated[0].owner["Shared"]["initial_shared_version"],
+            3
+        );
+    }
+
+    #[test]
+    fn test_parse_event_page() {
+        let page: EventPage = serde_json::from_str(
+            r#"{"data":[{"id":{"txDigest":"abc","eventSeq":"0"},"packageId":"0x5","transactionModule":"hello_world",
+                "sender":"0x1","type":"0x5::hello_world::Minted","parsedJson":{"value":"7"},"bcs":"2Ue","timestampMs":"1690000000000"}],
+                "nextCursor":{"txDigest":"abc","eventSeq":"0"},"hasNextPage":false}"#,
+        )
+        .unwrap();
+        assert_eq!(page.data[0].parsed_json["value"], "7");
+        assert_eq!(page.data[0].event_type, "0x5::hello_world::Minted");
+        assert_eq!(page.next_cursor.unwrap().event_seq, "0");
+    }
+
+    // effects 中包含 shared object 和 object owned object
+    #[test]
+    fn test_parse_dry_run_shared_object() {
+        let result: DryRunResult = serde_json::from_str(
+            r#"{"effects":{"messageVersion":"v1","status":{"status":"success"},"executedEpoch":"1",
+                "gasUsed":{"computationCost":"1000","storageCost":"2000","storageRebate":"500","nonRefundableStorageFee":"0"},
+                "modifiedAtVersions":[{"objectId":"0x6","sequenceNumber":"2"}],
+                "sharedObjects":[{"objectId":"0x6","version":2,"digest":"abc"}],
+                "transactionDigest":"def",
+                "created":[{"owner":"Immutable","reference":{"objectId":"0x7","version":3,"digest":"ghi"}}],
+                "mutated":[{"owner":{"Shared":{"initial_shared_version":1}},"reference":{"objectId":"0x6","version":3,"digest":"jkl"}},
+                           {"owner":{"ObjectOwner":"0x3"},"reference":{"objectId":"0x8","version":3,"digest":"mno"}},
+                           {"owner":{"AddressOwner":"0x1"},"reference":{"objectId":"0x1","version":3,"digest":"pqr"}}],
+                "gasObject":{"owner":{"AddressOwner":"0x1"},"reference":{"objectId":"0x1","version":3,"digest":"pqr"}},
+                "dependencies":["stu"]},
+                "events":[],"objectChanges":[],"balanceChanges":[],"input":{}}"#,
+        )
+        .unwrap();
+        assert!(result.failure().is_none());
+        assert_eq!(result.gas_used().unwrap().computation_cost, "1000");
+        let effects = result.effects.unwrap();
+        assert_eq!(effects.find_imutable_object(), vec!["0x7"]);
+        assert_eq!(effects.mutated[1].owner["ObjectOwner"], "0x3");
+    }
+
+    #[test]
+    fn test_parse_dev_inspect() {
+        let result: DevInspectResult = serde_json::from_str(
+            r#"{"effects":null,"events":[],"error":null,
+                "results":[{"mutableReferenceOutputs":[],"returnValues":[[[7,0,0,0,0,0,0,0],"u64"]]}]}"#,
+        )
+        .unwrap();
+        assert!(result.failure().is_none());
+        assert_eq!(result.return_values(0)[0].0, vec![7, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(result.return_values(1).is_empty());
+
+        let changes: Vec<ObjectChange> = serde_json::from_str(
+            r#"[{"type":"published","packageId":"0x5","version":"1","digest":"abc","modules":["hello_world"]},
+                {"type":"mutated","sender":"0x1","owner":{"AddressOwner":"0x1"},"objectType":"0x2::coin::Coin<0x2::sui::SUI>",
+                 "objectId":"0x6","version":"3","previousVersion":"2","digest":"def"}]"#,
+        )
+        .unwrap();
+        assert_eq!(changes[0].object_id(), "0x5");
+        assert!(
+            matches!(&changes[1], ObjectChange::Mutated { previous_version, .. } if previous_version == "2")
+        );
+    }
 }
@@ -0,0 +1,57 @@
+use rand::Rng;
+use std::time::Duration;
+
+// 只读请求遇到网络错误或者 429 / 5xx 时的重试策略
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    // 第 attempt 次重试前等待的时间，指数退避并在 [delay/2, delay] 之间随机
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(16));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let millis = delay.as_millis() as u64;
+        if millis < 2 {
+            return delay;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2, millis + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::default();
+        for attempt in 0..10 {
+            let delay = policy.delay(attempt);
+            let expected = (policy.base_delay * 2u32.pow(attempt)).min(policy.max_delay);
+            assert!(delay <= expected);
+            assert!(delay >= expected / 2);
+        }
+        assert_eq!(RetryPolicy::none().max_retries, 0);
+    }
+}
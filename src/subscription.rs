@@ -0,0 +1,307 @@
+use crate::config::SuiEnv;
+use crate::error::SuiError;
+use crate::network::Network;
+use crate::payload::{EventFilter, Payload, TransactionFilter};
+use crate::response::{RpcError, SuiEvent, TransactionEffects};
+use crate::retry::RetryPolicy;
+use futures::{stream, SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{to_value, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+const CHANNEL_SIZE: usize = 256;
+
+// websocket 订阅，连接断开后自动重连并重新订阅
+// 断开期间的 event 不会补发，需要不丢数据时使用 EventFollower
+pub struct SubscriptionClient {
+    url: String,
+    debug: bool,
+    // max_retries 为连续重连失败的次数上限
+    reconnect: RetryPolicy,
+}
+
+impl SubscriptionClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            debug: false,
+            reconnect: RetryPolicy {
+                max_retries: u32::MAX,
+                base_delay: Duration::from_millis(500),
+                max_delay: Duration::from_secs(30),
+            },
+        }
+    }
+
+    pub fn from_network(network: &Network) -> Self {
+        Self::new(&network.get_ws_url())
+    }
+
+    // 使用 client.yaml 中 env 配置的 ws 地址
+    pub fn from_sui_env(sui_env: &SuiEnv) -> Self {
+        Self::new(&sui_env.ws_url())
+    }
+
+    pub fn set_debug(&mut self) {
+        self.debug = true;
+    }
+
+    pub fn set_reconnect_policy(&mut self, reconnect: RetryPolicy) {
+        self.reconnect = reconnect;
+    }
+
+    pub fn subscribe_events(
+        &self,
+        filter: EventFilter,
+    ) -> impl Stream<Item = Result<SuiEvent, SuiError>> {
+        self.subscribe("suix_subscribeEvent", vec![to_value(filter).unwrap()])
+    }
+
+    pub fn subscribe_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> impl Stream<Item = Result<TransactionEffects, SuiError>> {
+        self.subscribe("suix_subscribeTransaction", vec![to_value(filter).unwrap()])
+    }
+
+    // 后台任务负责连接和重连，通知通过 channel 返回，stream 被 drop 后任务退出
+    fn subscribe<T: DeserializeOwned + Send + 'static>(
+        &self,
+        method: &str,
+        params: Vec<Value>,
+    ) -> impl Stream<Item = Result<T, SuiError>> {
+        let (tx, rx) = mpsc::channel(CHANNEL_SIZE);
+        let session = Session {
+            url: self.url.clone(),
+            method: method.to_string(),
+            params,
+            debug: self.debug,
+            reconnect: self.reconnect,
+        };
+        tokio::spawn(session.run(tx));
+        stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })
+    }
+}
+
+struct Session {
+    url: String,
+    method: String,
+    params: Vec<Value>,
+    debug: bool,
+    reconnect: RetryPolicy,
+}
+
+impl Session {
+    async fn run<T: DeserializeOwned>(self, tx: mpsc::Sender<Result<T, SuiError>>) {
+        let mut failures: u32 = 0;
+        loop {
+            let result = self.connect_once(&tx, &mut failures).await;
+            if tx.is_closed() {
+                return;
+            }
+            if let Err(err) = result {
+                // 订阅参数错误等 rpc error 重连也无法恢复
+                if !err.is_retryable() || failures >= self.reconnect.max_retries {
+                    let _ = tx.send(Err(err)).await;
+                    return;
+                }
+                if self.debug {
+                    println!("subscription {} dropped : {}", self.method, err);
+                }
+            }
+            tokio::time::sleep(self.reconnect.delay(failures)).await;
+            failures = failures.saturating_add(1);
+        }
+    }
+
+    // 返回 Ok 表示接收方已经关闭
+    async fn connect_once<T: DeserializeOwned>(
+        &self,
+        tx: &mpsc::Sender<Result<T, SuiError>>,
+        failures: &mut u32,
+    ) -> Result<(), SuiError> {
+        let (mut socket, _) = connect_async(self.url.as_str())
+            .await
+            .map_err(|err| SuiError::WebSocket(err.to_string()))?;
+        let payload = Payload::build(self.method.clone(), self.params.clone());
+        socket
+            .send(Message::Text(serde_json::to_string(&payload)?))
+            .await
+            .map_err(|err| SuiError::WebSocket(err.to_string()))?;
+
+        let mut subscription: Option<Value> = None;
+        loop {
+            // 接收方关闭后立即退出，不用等下一条消息
+            let message = tokio::select! {
+                message = socket.next() => message,
+                _ = tx.closed() => return Ok(()),
+            };
+            let message = match message {
+                Some(message) => message,
+                None => break,
+            };
+            let text = match message.map_err(|err| SuiError::WebSocket(err.to_string()))? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            // 无法解析的 frame 直接跳过，不影响后面的通知
+            let data: Value = match serde_json::from_str(&text) {
+                Ok(data) => data,
+                Err(err) => {
+                    if self.debug {
+                        println!("subscription {} skip frame : {}", self.method, err);
+                    }
+                    continue;
+                }
+            };
+            // 订阅请求的返回，result 为 subscription id
+            if data["id"].as_u64() == Some(payload.id()) {
+                if !data["error"].is_null() {
+                    let err: RpcError = serde_json::from_value(data["error"].clone())?;
+                    return Err(err.into());
+                }
+                subscription = Some(data["result"].clone());
+                *failures = 0;
+                continue;
+            }
+            let params = &data["params"];
+            if subscription.is_none() || Some(&params["subscription"]) != subscription.as_ref() {
+                continue;
+            }
+            let item = serde_json::from_value(params["result"].clone()).map_err(SuiError::from);
+            if tx.send(item).await.is_err() {
+                return Ok(());
+            }
+        }
+        Err(SuiError::WebSocket("connection closed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    fn event(seq: u64) -> Value {
+        json!({
+            "id": {"txDigest": "abc", "eventSeq": seq.to_string()},
+            "packageId": "0x5",
+            "transactionModule": "hello_world",
+            "sender": "0x1",
+            "type": "0x5::hello_world::Minted",
+            "parsedJson": {"seq": seq},
+            "bcs": ""
+        })
+    }
+
+    // 每个连接先发送一个无法解析的 frame ，回复订阅请求后依次发送 rounds 中的通知，最后一个连接之外的连接随后断开
+    async fn mock_ws(method: &'static str, params: Value, rounds: Vec<Vec<Value>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let last = rounds.len() - 1;
+        tokio::spawn(async move {
+            for (round, items) in rounds.into_iter().enumerate() {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let request: Value = match socket.next().await.unwrap().unwrap() {
+                    Message::Text(text) => serde_json::from_str(&text).unwrap(),
+                    _ => panic!("expect subscribe request"),
+                };
+                assert_eq!(request["method"], method);
+                assert_eq!(request["params"][0], params);
+                socket
+                    .send(Message::Text("not json".to_string()))
+                    .await
+                    .unwrap();
+                let reply = json!({"jsonrpc": "2.0", "id": request["id"], "result": round + 7});
+                socket.send(Message::Text(reply.to_string())).await.unwrap();
+                for item in items {
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": method,
+                        "params": {"subscription": round + 7, "result": item}
+                    });
+                    socket
+                        .send(Message::Text(notification.to_string()))
+                        .await
+                        .unwrap();
+                }
+                if round < last {
+                    socket.close(None).await.unwrap();
+                } else {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        });
+        url
+    }
+
+    // 第一个连接发送两条通知后断开，第二个连接继续发送
+    #[tokio::test]
+    async fn test_resubscribe_after_drop() {
+        let url = mock_ws(
+            "suix_subscribeEvent",
+            json!({"Package": "0x5"}),
+            vec![vec![event(0), event(1)], vec![event(2)]],
+        )
+        .await;
+
+        let mut client = SubscriptionClient::new(&url);
+        client.set_reconnect_policy(RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        });
+        let events: Vec<SuiEvent> = client
+            .subscribe_events(EventFilter::package("0x5"))
+            .take(3)
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+        let seqs: Vec<&str> = events.iter().map(|e| e.id.event_seq.as_str()).collect();
+        assert_eq!(seqs, vec!["0", "1", "2"]);
+    }
+
+    // effects 中包含 shared object
+    #[tokio::test]
+    async fn test_subscribe_transactions() {
+        let effects = json!({
+            "messageVersion": "v1",
+            "status": {"status": "success"},
+            "executedEpoch": "5",
+            "gasUsed": {"computationCost": "750000", "storageCost": "2432000", "storageRebate": "2407680", "nonRefundableStorageFee": "24320"},
+            "sharedObjects": [{"objectId": "0x6", "version": 8, "digest": "ghi"}],
+            "transactionDigest": "def",
+            "mutated": [{"owner": {"Shared": {"initial_shared_version": 3}}, "reference": {"objectId": "0x6", "version": 9, "digest": "jkl"}}],
+            "gasObject": {"owner": {"AddressOwner": "0x1"}, "reference": {"objectId": "0x1", "version": 9, "digest": "mno"}}
+        });
+        let url = mock_ws(
+            "suix_subscribeTransaction",
+            json!({"FromAddress": "0x1"}),
+            vec![vec![effects]],
+        )
+        .await;
+        let client = SubscriptionClient::new(&url);
+        let mut stream = Box::pin(
+            client.subscribe_transactions(TransactionFilter::FromAddress("0x1".to_string())),
+        );
+        let effects = stream.next().await.unwrap().unwrap();
+        assert!(effects.failure().is_none());
+        assert_eq!(effects.gas_used().computation_cost, "750000");
+    }
+
+    #[test]
+    fn test_ws_url() {
+        assert_eq!(
+            Network::Testnet.get_ws_url(),
+            "wss://fullnode.testnet.sui.io:443"
+        );
+        assert_eq!(Network::Localnet.get_ws_url(), "ws://127.0.0.1:9000");
+    }
+}
@@ -14,7 +14,6 @@ use std::{
     io::Read,
     thread,
     time::Duration,
-    vec,
 };
 
 pub const ADVISE_GAS_BUDGET: u64 = 300_000_000;
@@ -71,7 +70,7 @@ pub fn mark_with_style(title: String, style: &Style) {
 }
 
 pub fn random_style() -> Style {
-    let styles = vec![
+    let styles = [
         Color::Red.italic(),
         Color::Green.italic(),
         Color::Yellow.italic(),
@@ -103,6 +102,32 @@ pub fn base64_encode(data: &[u8]) -> String {
     engine.encode(data)
 }
 
+// bcs 中 vector 的长度使用 uleb128 编码
+pub fn uleb128_encode(mut value: usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+// 返回解码后的值和占用的字节数
+pub fn uleb128_decode(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value: usize = 0;
+    for (idx, byte) in data.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as usize) << (7 * idx);
+        if byte & 0x80 == 0 {
+            return Some((value, idx + 1));
+        }
+    }
+    None
+}
+
 pub fn sleep_with_message(message: String) {
     println!("{}", message);
     thread::sleep(Duration::from_secs(5));
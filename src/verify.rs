@@ -0,0 +1,208 @@
+use crate::account::{
+    intent_message, msg_hash, personal_message_bytes, public_key_to_address, IntentScope,
+    SignatureScheme,
+};
+use crate::multisig::decode_multisig;
+use crate::utils::{base64_decode, CustomErr};
+use std::error::Error;
+
+// 地址统一为 0x 开头，64 位小写 hex
+pub fn normalize_address(address: &str) -> String {
+    let hex_part = address.trim().trim_start_matches("0x").to_lowercase();
+    format!("0x{:0>64}", hex_part)
+}
+
+// signature 格式为 flag || sig || pubkey，校验通过时返回签名类型和公钥
+fn verify_digest<'a>(
+    digest: &[u8],
+    signature: &'a [u8],
+) -> Result<(SignatureScheme, &'a [u8]), Box<dyn Error>> {
+    if signature.len() < 65 {
+        return Err(CustomErr::new_box("signature is too short"));
+    }
+    let scheme = SignatureScheme::from_flag(signature[0])?;
+    let (sig_bytes, public_key) = signature[1..].split_at(64);
+    match scheme {
+        SignatureScheme::ED25519 => {
+            let key = ed25519_dalek::PublicKey::from_bytes(public_key)?;
+            let sig = ed25519_dalek::Signature::from_bytes(sig_bytes)?;
+            key.verify_strict(digest, &sig)?;
+        }
+        SignatureScheme::Secp256k1 => {
+            use k256::ecdsa::signature::Verifier;
+            let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)?;
+            let sig = k256::ecdsa::Signature::from_slice(sig_bytes)?;
+            if sig.normalize_s().is_some() {
+                return Err(CustomErr::new_box("secp256k1 signature is not normalized"));
+            }
+            key.verify(digest, &sig)?;
+        }
+        SignatureScheme::Secp256r1 => {
+            use p256::ecdsa::signature::Verifier;
+            let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)?;
+            let sig = p256::ecdsa::Signature::from_slice(sig_bytes)?;
+            if sig.normalize_s().is_some() {
+                return Err(CustomErr::new_box("secp256r1 signature is not normalized"));
+            }
+            key.verify(digest, &sig)?;
+        }
+        SignatureScheme::MultiSig => {
+            return Err(CustomErr::new_box("multisig signature is not supported"));
+        }
+    }
+    Ok((scheme, public_key))
+}
+
+// multisig 中每个签名都必须有效，且签名成员的权重之和达到 threshold
+fn verify_multisig_digest(digest: &[u8], signature: &[u8]) -> Result<String, Box<dyn Error>> {
+    let (multisig, signatures) = decode_multisig(signature)?;
+    let mut weight: u16 = 0;
+    for (idx, member_signature) in signatures {
+        verify_digest(digest, &member_signature)?;
+        weight += multisig.public_keys()[idx].weight as u16;
+    }
+    if weight < multisig.threshold() {
+        return Err(CustomErr::new_box(&format!(
+            "signatures weight {} is less than threshold {}",
+            weight,
+            multisig.threshold()
+        )));
+    }
+    Ok(multisig.to_address())
+}
+
+pub fn verify_intent_signature(
+    scope: IntentScope,
+    msg_bytes: &[u8],
+    signature: &[u8],
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    let digest = msg_hash(&intent_message(scope, msg_bytes));
+    let signer = if signature.first() == Some(&SignatureScheme::MultiSig.flag()) {
+        verify_multisig_digest(digest.as_bytes(), signature)?
+    } else {
+        let (scheme, public_key) = verify_digest(digest.as_bytes(), signature)?;
+        public_key_to_address(scheme, public_key)
+    };
+    if signer != normalize_address(address) {
+        return Err(CustomErr::new_box(
+            "signature public key does not match address",
+        ));
+    }
+    Ok(())
+}
+
+// tx_bytes 和 signature 都是 base64 编码，与 sui_executeTransactionBlock 的参数一致
+pub fn verify_transaction_signature(
+    tx_bytes: &str,
+    signature: &str,
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    let tx_data = base64_decode(tx_bytes)?;
+    let signature = base64_decode(signature)?;
+    verify_intent_signature(IntentScope::TransactionData, &tx_data, &signature, address)
+}
+
+// signature_b64 为钱包返回的 base64 编码的 sui signature
+pub fn verify_personal_message(
+    message: &[u8],
+    signature_b64: &str,
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    let signature = base64_decode(signature_b64)?;
+    verify_intent_signature(
+        IntentScope::PersonalMessage,
+        &personal_message_bytes(message),
+        &signature,
+        address,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::SuiAccount;
+    use crate::multisig::{MultiSigAccount, WeightedPublicKey};
+    use crate::utils::base64_encode;
+
+    #[test]
+    fn test_verify_personal_message() {
+        let message = b"hello sui";
+        for scheme in [
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::Secp256r1,
+        ] {
+            let account = SuiAccount::generate(scheme).unwrap();
+            let other = SuiAccount::generate(scheme).unwrap();
+            let signature = base64_encode(&account.sign_personal_message(message));
+            assert!(verify_personal_message(message, &signature, &account.to_address()).is_ok());
+            assert!(
+                verify_personal_message(b"hello move", &signature, &account.to_address()).is_err()
+            );
+            assert!(verify_personal_message(message, &signature, &other.to_address()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_transaction_signature() {
+        let tx_bytes = base64_encode(&[7u8, 7, 7, 7]);
+        let account = SuiAccount::generate(SignatureScheme::Secp256k1).unwrap();
+        let signature = base64_encode(&account.sign_data(&tx_bytes, IntentScope::TransactionData));
+        assert!(verify_transaction_signature(&tx_bytes, &signature, &account.to_address()).is_ok());
+
+        // personal message 的签名不能用作交易签名
+        let message_signature = base64_encode(&account.sign_personal_message(&[7u8, 7, 7, 7]));
+        assert!(
+            verify_transaction_signature(&tx_bytes, &message_signature, &account.to_address())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_multisig_transaction_signature() {
+        let accounts = [
+            SuiAccount::new_account(),
+            SuiAccount::generate(SignatureScheme::Secp256k1).unwrap(),
+            SuiAccount::generate(SignatureScheme::Secp256r1).unwrap(),
+        ];
+        let multisig = MultiSigAccount::new(
+            accounts
+                .iter()
+                .map(|account| WeightedPublicKey::from_account(account, 1))
+                .collect(),
+            2,
+        )
+        .unwrap();
+        let tx_bytes = base64_encode(&[1u8, 2, 3]);
+        let combined = multisig
+            .sign_with_accounts(&tx_bytes, &[&accounts[1], &accounts[2]])
+            .unwrap();
+        let signature = base64_encode(&combined);
+        assert!(
+            verify_transaction_signature(&tx_bytes, &signature, &multisig.to_address()).is_ok()
+        );
+        assert!(
+            verify_transaction_signature(&tx_bytes, &signature, &accounts[1].to_address()).is_err()
+        );
+
+        // 篡改其中一个成员的签名
+        let mut tampered = combined.clone();
+        tampered[5] ^= 0xff;
+        assert!(verify_transaction_signature(
+            &tx_bytes,
+            &base64_encode(&tampered),
+            &multisig.to_address()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_personal_message_bytes() {
+        assert_eq!(personal_message_bytes(b"abc"), vec![3, 97, 98, 99]);
+        assert_eq!(
+            personal_message_bytes(&[0u8; 200])[..2].to_vec(),
+            vec![200, 1]
+        );
+    }
+}